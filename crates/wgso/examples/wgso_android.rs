@@ -12,6 +12,7 @@ pub extern "Rust" fn android_main(app: android_activity::AndroidApp) {
         path: "".into(),
         buffer: vec![],
         fps: false,
+        adapter: wgso::AdapterArgs::default(),
     };
     args.run_android(app, PROJECT_DIR.clone());
 }
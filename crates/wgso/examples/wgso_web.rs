@@ -9,6 +9,7 @@ fn main() {
         path: "".into(),
         buffer: vec![],
         fps: false,
+        adapter: wgso::AdapterArgs::default(),
     };
     args.run_web(PROJECT_DIR.clone());
 }
@@ -1,18 +1,21 @@
 #![allow(clippy::print_stdout, clippy::use_debug)]
 
+use crate::config::{PowerPreferenceConfig, ProgramConfig, CONFIG_FILE_NAME};
+use crate::doc::Documentation;
+use crate::graph::Graph;
 use crate::runner::gpu;
-use crate::{Program, Runner};
-use clap::Parser;
+use crate::{AdapterOptions, Event, LanguageServer, Program, Runner, SourceFolder};
+use clap::{Parser, Subcommand, ValueEnum};
 use futures::channel::oneshot::{Receiver, Sender};
 use std::fmt::Display;
 use std::fs;
 use std::path::{Path, PathBuf};
+use wgpu::Backends;
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
-use winit::event::{DeviceEvent, DeviceId, WindowEvent};
+use winit::event::{DeviceEvent, DeviceId, Ime, WindowEvent};
 use winit::event_loop::{ActiveEventLoop, EventLoop};
-use winit::keyboard::PhysicalKey;
-use winit::window::WindowId;
+use winit::window::{Fullscreen, WindowId};
 // coverage: off (not easy to test)
 
 #[cfg(target_os = "android")]
@@ -25,10 +28,24 @@ pub(crate) static ANDROID_APP: std::sync::OnceLock<android_activity::AndroidApp>
 pub enum Args {
     /// Install dependencies of a WGSO program.
     Install(InstallArgs),
+    /// Update dependencies of a WGSO program and their entry in `wgso.lock` file.
+    Update(UpdateArgs),
+    /// Inspect dependencies of a WGSO program.
+    Deps(DepsArgs),
+    /// List available GPU adapters with their backend.
+    Adapters(AdaptersArgs),
     /// Run a WGSO program.
     Run(RunArgs),
     /// Display the analysis result of a parsed WGSO program.
     Analyze(AnalyzeArgs),
+    /// Generate Markdown documentation of a WGSO program from `///` comments.
+    Doc(DocArgs),
+    /// Export the dependency graph of a WGSO program.
+    Graph(GraphArgs),
+    /// Display the ordered list of shader executions of a WGSO program.
+    Plan(PlanArgs),
+    /// Start a language server communicating through standard input and output.
+    Lsp(LspArgs),
 }
 
 impl Args {
@@ -36,8 +53,15 @@ impl Args {
     pub fn run(self) {
         match self {
             Self::Install(args) => args.run(),
+            Self::Update(args) => args.run(),
+            Self::Deps(args) => args.run(),
+            Self::Adapters(args) => args.run(),
             Self::Run(args) => args.run(),
             Self::Analyze(args) => args.run(),
+            Self::Doc(args) => args.run(),
+            Self::Graph(args) => args.run(),
+            Self::Plan(args) => args.run(),
+            Self::Lsp(args) => args.run(),
         }
     }
 }
@@ -51,6 +75,9 @@ pub struct InstallArgs {
     /// Force retrieval of all dependencies, even if they have already been retrieved.
     #[clap(long, short, action)]
     force: bool,
+    /// Retrieve remote dependencies only from the dependency cache.
+    #[clap(long, action)]
+    offline: bool,
 }
 
 impl InstallArgs {
@@ -66,12 +93,150 @@ impl InstallArgs {
                 }
             }
         }
-        if let Err(error) = wgso_deps::retrieve_dependencies(self.path.join("wgso.yaml")) {
+        let options = wgso_deps::RetrieveOptions {
+            is_offline: self.offline,
+            ..wgso_deps::RetrieveOptions::default()
+        };
+        if let Err(error) = wgso_deps::retrieve_dependencies_with_options(
+            self.path.join(CONFIG_FILE_NAME),
+            &options,
+        ) {
             exit_on_error(error);
         }
     }
 }
 
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct UpdateArgs {
+    /// Path to the WGSO program directory containing a `wgso.yaml` file.
+    path: PathBuf,
+    /// Name of the dependency to update (all dependencies are updated by default).
+    name: Option<String>,
+    /// Retrieve remote dependencies only from the dependency cache.
+    #[clap(long, action)]
+    offline: bool,
+}
+
+impl UpdateArgs {
+    fn run(self) {
+        let options = wgso_deps::RetrieveOptions {
+            update: self.name.map_or(wgso_deps::UpdatePolicy::All, |name| {
+                wgso_deps::UpdatePolicy::Only(vec![name])
+            }),
+            is_offline: self.offline,
+            cache_path: None,
+        };
+        if let Err(error) = wgso_deps::retrieve_dependencies_with_options(
+            self.path.join(CONFIG_FILE_NAME),
+            &options,
+        ) {
+            exit_on_error(error);
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct DepsArgs {
+    #[command(subcommand)]
+    command: DepsCommand,
+}
+
+impl DepsArgs {
+    fn run(self) {
+        match self.command {
+            DepsCommand::Tree(args) => args.run(),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum DepsCommand {
+    /// Display the resolved dependency tree with dependency sources.
+    Tree(DepsTreeArgs),
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct DepsTreeArgs {
+    /// Path to the WGSO program directory containing a `wgso.yaml` file.
+    path: PathBuf,
+    /// Retrieve remote dependencies only from the dependency cache.
+    #[clap(long, action)]
+    offline: bool,
+}
+
+impl DepsTreeArgs {
+    fn run(self) {
+        let options = wgso_deps::RetrieveOptions {
+            is_offline: self.offline,
+            ..wgso_deps::RetrieveOptions::default()
+        };
+        match wgso_deps::resolve_dependencies(self.path.join(CONFIG_FILE_NAME), &options) {
+            Ok(tree) => print!("{tree}"),
+            Err(error) => exit_on_error(error),
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct AdaptersArgs {
+    #[command(flatten)]
+    adapter: AdapterArgs,
+}
+
+impl AdaptersArgs {
+    fn run(self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        for (index, info) in crate::adapters(&self.adapter.options())
+            .into_iter()
+            .enumerate()
+        {
+            println!(
+                "{index}: {} (backend: {}, type: {:?})",
+                info.name, info.backend, info.device_type
+            );
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(clap::Args, Debug, Default)]
+pub struct AdapterArgs {
+    /// Comma-separated list of allowed backends (e.g. `vulkan,gl`).
+    ///
+    /// By default, backends of `WGPU_BACKEND` environment variable are used if defined,
+    /// otherwise all backends are allowed.
+    #[arg(long, value_parser = parse_backends)]
+    backend: Option<Backends>,
+    /// Index or name of the GPU adapter to use, as listed by `wgso adapters`.
+    #[arg(long)]
+    adapter: Option<String>,
+    /// Power preference used to select the GPU adapter, overriding the program configuration.
+    #[arg(long, value_enum)]
+    power_preference: Option<PowerPreferenceConfig>,
+    /// Force the use of the fallback GPU adapter (e.g. a software renderer).
+    #[clap(long, action)]
+    fallback_adapter: bool,
+}
+
+impl AdapterArgs {
+    fn options(&self) -> AdapterOptions {
+        AdapterOptions {
+            backends: self.backend,
+            adapter: self.adapter.clone(),
+            power_preference: self.power_preference.map(Into::into),
+            is_fallback_forced: self.fallback_adapter,
+        }
+    }
+}
+
 #[doc(hidden)]
 #[derive(Parser, Debug)]
 #[command(version, about, long_about=None)]
@@ -84,18 +249,20 @@ pub struct RunArgs {
     /// Print FPS in standard output.
     #[clap(long, short, action)]
     pub fps: bool,
+    /// Options to select the GPU adapter.
+    #[command(flatten)]
+    pub adapter: AdapterArgs,
 }
 
 impl RunArgs {
-    const DEFAULT_SIZE: (u32, u32) = (800, 600);
-
     fn run(self) {
         let path = self.path.clone();
+        let config = Self::load_config(&path.as_path());
+        let adapter_options = self.adapter.options();
         let mut runner = WindowRunner::new(self, move |event_loop, sender| {
-            let window = gpu::create_window(event_loop, Self::DEFAULT_SIZE);
-            sender
-                .send(Runner::new(path.as_path(), Some(window), None))
-                .expect("Cannot send created runner");
+            let window = gpu::create_window(event_loop, &config);
+            let runner = Runner::with_adapter(path.as_path(), Some(window), None, &adapter_options);
+            sender.send(runner).expect("Cannot send created runner");
         });
         EventLoop::builder()
             .build()
@@ -110,8 +277,10 @@ impl RunArgs {
         use winit::platform::web::{EventLoopExtWebSys, WindowExtWebSys};
         std::panic::set_hook(Box::new(console_error_panic_hook::hook));
         let _ = console_log::init_with_level(log::Level::Info);
+        let config = Self::load_config(&source);
+        let adapter_options = self.adapter.options();
         let runner = WindowRunner::new(self, move |event_loop, sender| {
-            let window = gpu::create_window(event_loop, Self::DEFAULT_SIZE);
+            let window = gpu::create_window(event_loop, &config);
             if let Some(canvas) = window.canvas() {
                 canvas.set_id("wgso");
                 web_sys::window()
@@ -121,10 +290,11 @@ impl RunArgs {
                     .expect("cannot append canvas to document body");
             }
             let source = source.clone();
+            let adapter_options = adapter_options.clone();
             wasm_bindgen_futures::spawn_local(async move {
-                sender
-                    .send(Runner::new_async(source, Some(window), None).await)
-                    .expect("Cannot send created runner");
+                let runner =
+                    Runner::with_adapter_async(source, Some(window), None, &adapter_options).await;
+                sender.send(runner).expect("Cannot send created runner");
             });
         });
         EventLoop::builder()
@@ -141,11 +311,12 @@ impl RunArgs {
         source: impl crate::SourceFolder + Send + 'static,
     ) {
         use winit::platform::android::EventLoopBuilderExtAndroid;
+        let config = Self::load_config(&source);
+        let adapter_options = self.adapter.options();
         let mut runner = WindowRunner::new(self, move |event_loop, sender| {
-            let window = gpu::create_window(event_loop, Self::DEFAULT_SIZE);
-            sender
-                .send(Runner::new(source.clone(), Some(window), None))
-                .expect("Cannot send created runner");
+            let window = gpu::create_window(event_loop, &config);
+            let runner = Runner::with_adapter(source.clone(), Some(window), None, &adapter_options);
+            sender.send(runner).expect("Cannot send created runner");
         });
         ANDROID_APP.get_or_init(|| android_app.clone());
         EventLoop::builder()
@@ -155,6 +326,11 @@ impl RunArgs {
             .run_app(&mut runner)
             .expect("event loop failed");
     }
+
+    fn load_config(source: &impl SourceFolder) -> ProgramConfig {
+        // an invalid configuration is reported as a program error once the runner is created
+        ProgramConfig::load(source).unwrap_or_default()
+    }
 }
 
 #[doc(hidden)]
@@ -163,18 +339,128 @@ impl RunArgs {
 pub struct AnalyzeArgs {
     /// Path to the WGSO program directory to analyze.
     path: String,
+    #[command(flatten)]
+    adapter: AdapterArgs,
 }
 
 impl AnalyzeArgs {
     #[allow(clippy::similar_names)]
     fn run(self) {
-        match Runner::new(Path::new(&self.path), None, None) {
+        let adapter_options = self.adapter.options();
+        match Runner::with_adapter(Path::new(&self.path), None, None, &adapter_options) {
             Ok(runner) => println!("{runner:#?}"),
             Err(program) => exit_on_error(program.render_errors()),
         }
     }
 }
 
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct DocArgs {
+    /// Path to the WGSO program directory to document.
+    path: PathBuf,
+    /// Path to the directory where the documentation is generated.
+    #[arg(short, long, default_value = "doc")]
+    output: PathBuf,
+}
+
+impl DocArgs {
+    fn run(self) {
+        let program = Program::parse(self.path.as_path());
+        if !program.errors.is_empty() {
+            exit_on_error(program.with_sorted_errors().render_errors());
+            return;
+        }
+        if let Err(error) = Documentation::new(&program).write(&self.output) {
+            exit_on_error(format!("Cannot write documentation: {error}"));
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct GraphArgs {
+    /// Path to the WGSO program directory to analyze.
+    path: PathBuf,
+    /// Format of the exported graph.
+    #[arg(short, long, value_enum, default_value_t = GraphFormat::Dot)]
+    format: GraphFormat,
+    /// Path to the file where the graph is written instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl GraphArgs {
+    fn run(self) {
+        let program = Program::parse(self.path.as_path());
+        if !program.errors.is_empty() {
+            exit_on_error(program.with_sorted_errors().render_errors());
+            return;
+        }
+        let graph = Graph::new(&program);
+        let graph = match self.format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Json => graph.to_json(),
+        };
+        if let Some(output) = &self.output {
+            if let Err(error) = fs::write(output, graph) {
+                exit_on_error(format!("Cannot write graph: {error}"));
+            }
+        } else {
+            print!("{graph}");
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct PlanArgs {
+    /// Path to the WGSO program directory to analyze.
+    path: PathBuf,
+}
+
+impl PlanArgs {
+    fn run(self) {
+        let program = Program::parse(self.path.as_path());
+        if let Ok(plan) = program.execution_plan() {
+            print!("{plan}");
+        } else {
+            exit_on_error(program.with_sorted_errors().render_errors());
+        }
+    }
+}
+
+#[doc(hidden)]
+#[derive(Parser, Debug)]
+#[command(version, about, long_about=None)]
+pub struct LspArgs {
+    /// Path to the WGSO program directory used if the editor doesn't provide any folder.
+    #[arg(default_value = ".")]
+    path: PathBuf,
+}
+
+impl LspArgs {
+    fn run(self) {
+        let (connection, io_threads) = lsp_server::Connection::stdio();
+        if let Err(error) = LanguageServer::new(connection, self.path).run() {
+            exit_on_error(error);
+        }
+        if let Err(error) = io_threads.join() {
+            exit_on_error(error);
+        }
+    }
+}
+
 struct WindowRunner {
     args: RunArgs,
     #[allow(clippy::type_complexity)]
@@ -198,7 +484,21 @@ impl ApplicationHandler for WindowRunner {
         if let Some(receiver) = &mut self.runner_receiver {
             if let Ok(Some(runner)) = receiver.try_recv() {
                 match runner {
-                    Ok(runner) => self.runner = Some(runner),
+                    Ok(mut runner) => {
+                        runner.enable_error_overlay();
+                        #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+                        match crate::GilrsGamepadBackend::new() {
+                            Ok(backend) => runner.set_gamepad_backend(backend),
+                            Err(error) => log::warn!("gamepads are not supported: {error}"),
+                        }
+                        if let Some(window) = runner.window() {
+                            window.set_ime_allowed(true);
+                            #[allow(clippy::cast_possible_truncation)]
+                            let scale_factor = window.scale_factor() as f32;
+                            runner.std_state.lifecycle.scale_factor = scale_factor;
+                        }
+                        self.runner = Some(runner);
+                    }
                     Err(program) => exit_on_error(program.render_errors()),
                 }
                 self.runner_receiver = None;
@@ -206,14 +506,30 @@ impl ApplicationHandler for WindowRunner {
         }
         if let Some(runner) = &mut self.runner {
             match event {
-                WindowEvent::RedrawRequested => self.update(),
+                WindowEvent::RedrawRequested => self.update(event_loop),
                 WindowEvent::CloseRequested => event_loop.exit(),
-                WindowEvent::Resized(size) => self.update_window_size(size),
+                WindowEvent::Resized(size) => {
+                    runner.std_state.lifecycle.is_just_resized = true;
+                    self.update_window_size(size);
+                }
+                WindowEvent::Focused(is_focused) => {
+                    runner.std_state.lifecycle.is_focused = is_focused;
+                }
+                WindowEvent::Occluded(is_occluded) => {
+                    runner.std_state.lifecycle.is_occluded = is_occluded;
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                    runner.std_state.lifecycle.scale_factor = scale_factor as f32;
+                }
                 WindowEvent::KeyboardInput { event, .. } => {
-                    if let PhysicalKey::Code(key) = event.physical_key {
-                        runner.std_state.keyboard.update_key(key, event.state);
-                    }
+                    runner.std_state.keyboard.update_key(&event);
                 }
+                WindowEvent::Ime(Ime::Enabled) => runner.std_state.keyboard.set_ime_enabled(true),
+                WindowEvent::Ime(Ime::Disabled) => {
+                    runner.std_state.keyboard.set_ime_enabled(false);
+                }
+                WindowEvent::Ime(Ime::Commit(text)) => runner.std_state.keyboard.add_text(&text),
                 WindowEvent::CursorMoved { position, .. } => {
                     runner.std_state.mouse.update_position(position);
                 }
@@ -265,6 +581,7 @@ impl WindowRunner {
     fn refresh_surface(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(runner) = &mut self.runner {
             runner.refresh_surface();
+            runner.std_state.lifecycle.is_just_resumed = true;
         } else {
             let (sender, receiver) = futures::channel::oneshot::channel();
             self.runner_receiver = Some(receiver);
@@ -272,14 +589,20 @@ impl WindowRunner {
         }
     }
 
-    fn update(&mut self) {
+    fn update(&mut self, event_loop: &ActiveEventLoop) {
         if let Some(runner) = &mut self.runner {
-            if let Err(program) = runner.reload_on_change() {
-                println!("{}", program.render_errors());
+            match runner.reload_on_change() {
+                Ok(Some(migration)) if !migration.is_empty() => print!("{migration}"),
+                Ok(_) => (),
+                Err(program) => println!("{}", program.render_errors()),
             }
             if let Err(program) = runner.run_step() {
                 exit_on_error(program.render_errors());
             }
+            for event in runner.drain_events() {
+                Self::handle_event(runner, event_loop, event);
+            }
+            runner.update_window();
             if self.args.fps {
                 println!("FPS: {}", (1. / runner.delta_secs()).round());
             }
@@ -289,6 +612,23 @@ impl WindowRunner {
         }
     }
 
+    fn handle_event(runner: &Runner, event_loop: &ActiveEventLoop, event: Event) {
+        let Some(window) = runner.window() else {
+            return;
+        };
+        match event.kind {
+            Event::EXIT => event_loop.exit(),
+            Event::TOGGLE_FULLSCREEN => window.set_fullscreen(
+                window
+                    .fullscreen()
+                    .is_none()
+                    .then_some(Fullscreen::Borderless(None)),
+            ),
+            Event::SET_CURSOR_VISIBILITY => window.set_cursor_visible(event.params[0] != 0),
+            _ => (),
+        }
+    }
+
     fn update_window_size(&mut self, size: PhysicalSize<u32>) {
         if let Some(runner) = &mut self.runner {
             runner.update_surface_size(size);
@@ -296,6 +636,15 @@ impl WindowRunner {
     }
 }
 
+fn parse_backends(backends: &str) -> Result<Backends, String> {
+    let parsed_backends = Backends::from_comma_list(backends);
+    if parsed_backends.is_empty() {
+        Err(format!("no valid backend found in `{backends}`"))
+    } else {
+        Ok(parsed_backends)
+    }
+}
+
 fn exit_on_error(error: impl Display) {
     println!("{error}");
     #[cfg(not(target_arch = "wasm32"))]
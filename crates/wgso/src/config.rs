@@ -0,0 +1,289 @@
+use crate::program::file::SourceFolder;
+use crate::program::section::Sections;
+use crate::Error;
+use clap::ValueEnum;
+use fxhash::FxHashSet;
+use naga::valid::Capabilities;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use wgpu::{Features, Limits, PowerPreference};
+
+/// The name of the configuration file of a WGSO program.
+pub(crate) const CONFIG_FILE_NAME: &str = "wgso.yaml";
+
+/// The configuration of a WGSO program, defined in the `program` section of `wgso.yaml`.
+///
+/// Default values are used if the section or the file doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ProgramConfig {
+    pub(crate) name: Option<String>,
+    pub(crate) version: Option<String>,
+    pub(crate) window: WindowConfig,
+    pub(crate) vsync: bool,
+    pub(crate) power_preference: PowerPreferenceConfig,
+    pub(crate) features: Vec<String>,
+    pub(crate) limits: BTreeMap<String, u64>,
+    pub(crate) enabled_toggles: Vec<String>,
+}
+
+impl Default for ProgramConfig {
+    fn default() -> Self {
+        Self {
+            name: None,
+            version: None,
+            window: WindowConfig::default(),
+            vsync: true,
+            power_preference: PowerPreferenceConfig::default(),
+            features: vec![],
+            limits: BTreeMap::new(),
+            enabled_toggles: vec![],
+        }
+    }
+}
+
+impl ProgramConfig {
+    /// Loads and validates the configuration of the program located in `source`.
+    pub(crate) fn load(source: &impl SourceFolder) -> Result<Self, Error> {
+        let path = Self::path(&source.path());
+        let Some(content) = source
+            .config()
+            .map_err(|error| Error::Io(path.clone(), error))?
+        else {
+            return Ok(Self::default());
+        };
+        let file: ConfigFile = serde_yml::from_str(&content)
+            .map_err(|error| Error::InvalidConfig(path.clone(), error.to_string()))?;
+        let config = file.program;
+        if let Some(version) = &config.version {
+            semver::Version::parse(version).map_err(|error| {
+                Error::InvalidConfig(
+                    path.clone(),
+                    format!("invalid version `{version}`: {error}"),
+                )
+            })?;
+        }
+        if config.window.size.0 == 0 || config.window.size.1 == 0 {
+            return Err(Error::InvalidConfig(
+                path,
+                "window size should be greater than zero".into(),
+            ));
+        }
+        config
+            .features()
+            .map_err(|message| Error::InvalidConfig(path.clone(), message))?;
+        config
+            .limits()
+            .map_err(|message| Error::InvalidConfig(path, message))?;
+        Ok(config)
+    }
+
+    /// Checks that enabled toggles correspond to toggle value storages of the program.
+    pub(crate) fn check_toggles(
+        &self,
+        sections: &Sections,
+        root_path: &Path,
+        errors: &mut Vec<Error>,
+    ) {
+        let toggle_paths: FxHashSet<_> = sections
+            .toggle_directives()
+            .map(|directive| directive.toggle_value_buffer().path())
+            .collect();
+        for toggle in &self.enabled_toggles {
+            if !toggle_paths.contains(toggle) {
+                errors.push(Error::InvalidConfig(
+                    Self::path(root_path),
+                    format!("no toggle found with value storage `{toggle}`"),
+                ));
+            }
+        }
+    }
+
+    pub(crate) fn path(root_path: &Path) -> PathBuf {
+        root_path.join(CONFIG_FILE_NAME)
+    }
+
+    /// Returns the window title, which is the program name by default.
+    pub(crate) fn title(&self) -> &str {
+        self.window
+            .title
+            .as_deref()
+            .or(self.name.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Returns the required GPU features.
+    ///
+    /// Feature names are in kebab case (e.g. `shader-f16`).
+    pub(crate) fn features(&self) -> Result<Features, String> {
+        self.features
+            .iter()
+            .try_fold(Features::empty(), |features, name| {
+                Features::from_name(&name.to_uppercase().replace('-', "_"))
+                    .map(|feature| features | feature)
+                    .ok_or_else(|| format!("unknown GPU feature `{name}`"))
+            })
+    }
+
+    /// Returns the Naga capabilities used to validate shaders, derived from the required
+    /// GPU features.
+    ///
+    /// This follows the mapping used by WGPU to validate shader modules, so that shaders using
+    /// a feature that is not required are rejected at parse time.
+    pub(crate) fn capabilities(&self) -> Capabilities {
+        let features = self
+            .features()
+            .expect("internal error: features should be validated");
+        [
+            (Features::PUSH_CONSTANTS, Capabilities::PUSH_CONSTANT),
+            (Features::SHADER_F64, Capabilities::FLOAT64),
+            (Features::SHADER_F16, Capabilities::SHADER_FLOAT16),
+            (
+                Features::SHADER_PRIMITIVE_INDEX,
+                Capabilities::PRIMITIVE_INDEX,
+            ),
+            (
+                Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+                Capabilities::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING
+                    | Capabilities::SAMPLER_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+                Capabilities::STORAGE_TEXTURE_ARRAY_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::UNIFORM_BUFFER_BINDING_ARRAYS,
+                Capabilities::UNIFORM_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+            ),
+            (
+                Features::TEXTURE_FORMAT_16BIT_NORM,
+                Capabilities::STORAGE_TEXTURE_16BIT_NORM_FORMATS,
+            ),
+            (Features::MULTIVIEW, Capabilities::MULTIVIEW),
+            (
+                Features::SHADER_EARLY_DEPTH_TEST,
+                Capabilities::EARLY_DEPTH_TEST,
+            ),
+            (Features::SHADER_INT64, Capabilities::SHADER_INT64),
+            (
+                Features::SHADER_INT64_ATOMIC_MIN_MAX,
+                Capabilities::SHADER_INT64_ATOMIC_MIN_MAX,
+            ),
+            (
+                Features::SHADER_INT64_ATOMIC_ALL_OPS,
+                Capabilities::SHADER_INT64_ATOMIC_MIN_MAX
+                    | Capabilities::SHADER_INT64_ATOMIC_ALL_OPS,
+            ),
+            (Features::TEXTURE_ATOMIC, Capabilities::TEXTURE_ATOMIC),
+            (
+                Features::TEXTURE_INT64_ATOMIC,
+                Capabilities::TEXTURE_INT64_ATOMIC,
+            ),
+            (
+                Features::SHADER_FLOAT32_ATOMIC,
+                Capabilities::SHADER_FLOAT32_ATOMIC,
+            ),
+            (
+                Features::DUAL_SOURCE_BLENDING,
+                Capabilities::DUAL_SOURCE_BLENDING,
+            ),
+            (Features::SUBGROUP, Capabilities::SUBGROUP),
+            (
+                Features::SUBGROUP_VERTEX,
+                Capabilities::SUBGROUP | Capabilities::SUBGROUP_VERTEX_STAGE,
+            ),
+            (Features::SUBGROUP_BARRIER, Capabilities::SUBGROUP_BARRIER),
+        ]
+        .into_iter()
+        .filter(|(feature, _)| features.contains(*feature))
+        .fold(
+            // capabilities depending on downlevel flags are supported by WebGPU adapters
+            Capabilities::MULTISAMPLED_SHADING | Capabilities::CUBE_ARRAY_TEXTURES,
+            |capabilities, (_, feature_capabilities)| capabilities | feature_capabilities,
+        )
+    }
+
+    /// Returns the required GPU limits.
+    ///
+    /// Limit names are in snake case (e.g. `max_bind_groups`).
+    /// Limits that are not configured have their default value.
+    pub(crate) fn limits(&self) -> Result<Limits, String> {
+        let mut limits = serde_json::to_value(Limits::default())
+            .expect("internal error: limits should be serializable");
+        let fields = limits
+            .as_object_mut()
+            .expect("internal error: limits should be serialized as an object");
+        for (name, &value) in &self.limits {
+            let field_name = snake_to_camel_case(name);
+            if !fields.contains_key(&field_name) {
+                return Err(format!("unknown GPU limit `{name}`"));
+            }
+            fields.insert(field_name, value.into());
+        }
+        serde_json::from_value(limits).map_err(|error| format!("invalid GPU limit: {error}"))
+    }
+}
+
+/// The window configuration of a WGSO program.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct WindowConfig {
+    pub(crate) title: Option<String>,
+    pub(crate) size: (u32, u32),
+    pub(crate) resizable: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            title: None,
+            size: (800, 600),
+            resizable: true,
+        }
+    }
+}
+
+/// The power preference used to select the GPU adapter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum PowerPreferenceConfig {
+    #[default]
+    None,
+    LowPower,
+    HighPerformance,
+}
+
+impl From<PowerPreferenceConfig> for PowerPreference {
+    fn from(preference: PowerPreferenceConfig) -> Self {
+        match preference {
+            PowerPreferenceConfig::None => Self::None,
+            PowerPreferenceConfig::LowPower => Self::LowPower,
+            PowerPreferenceConfig::HighPerformance => Self::HighPerformance,
+        }
+    }
+}
+
+// The `dependencies` section is validated by `wgso_deps`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    program: ProgramConfig,
+    #[serde(default, rename = "dependencies")]
+    _dependencies: serde_yml::Value,
+}
+
+fn snake_to_camel_case(name: &str) -> String {
+    let mut words = name.split('_');
+    words.next().unwrap_or_default().to_string()
+        + &words
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<String>()
+}
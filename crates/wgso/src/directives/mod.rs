@@ -1,6 +1,7 @@
 #![allow(clippy::multiple_inherent_impl)]
 
 use crate::directives::calls::DirectiveArg;
+use crate::program;
 use crate::program::module::Modules;
 use crate::program::type_::Type;
 use crate::Error;
@@ -103,6 +104,11 @@ impl Directive {
         (path, name)
     }
 
+    pub(crate) fn item_id(&self, root_path: &Path) -> String {
+        let (path, name) = self.item_ident(root_path);
+        format!("{}.{name}", program::dotted_path(root_path, &path))
+    }
+
     pub(crate) fn segment_path(&self, root_path: &Path) -> PathBuf {
         let is_relative = self
             .find_all_by_label("path_segment")
@@ -0,0 +1,539 @@
+use crate::directives::DirectiveKind;
+use crate::program::file::File;
+use crate::program::section::Section;
+use crate::program::wgsl::Item;
+use crate::Program;
+use itertools::Itertools;
+use std::fs;
+use std::io;
+use std::iter;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+const DOC_COMMENT_PREFIX: &str = "///";
+
+/// Markdown documentation generated from `///` comments of a WGSO program.
+#[derive(Debug)]
+pub(crate) struct Documentation {
+    pages: Vec<DocPage>,
+}
+
+impl Documentation {
+    pub(crate) fn new(program: &Program) -> Self {
+        let files: Vec<_> = program
+            .files
+            .iter()
+            .map(|file| DocFile::new(program, file))
+            .collect();
+        let index = DocPage {
+            path: PathBuf::from("index.md"),
+            content: Self::index_content(program, &files),
+        };
+        Self {
+            pages: iter::once(index)
+                .chain(files.iter().map(|file| DocPage {
+                    path: file.page_path.clone(),
+                    content: file.content(),
+                }))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn write(&self, output_path: &Path) -> io::Result<()> {
+        for page in &self.pages {
+            let path = output_path.join(&page.path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, &page.content)?;
+        }
+        Ok(())
+    }
+
+    fn index_content(program: &Program, files: &[DocFile]) -> String {
+        let title = program
+            .root_path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let links = files
+            .iter()
+            .flat_map(|file| {
+                let link = relative_link(Path::new("index.md"), &file.page_path);
+                iter::once(format!("- [`{}`]({link})", file.source_path)).chain(
+                    file.modules.iter().map(move |module| {
+                        format!(
+                            "  - [`{}`]({link}#{}){}",
+                            module.name,
+                            module.anchor(),
+                            module
+                                .doc
+                                .lines()
+                                .next()
+                                .map_or_else(String::new, |summary| format!(": {summary}"))
+                        )
+                    }),
+                )
+            })
+            .join("\n");
+        format!("# `{title}`\n\n{links}\n")
+    }
+}
+
+#[derive(Debug)]
+struct DocPage {
+    path: PathBuf,
+    content: String,
+}
+
+#[derive(Debug)]
+struct DocFile {
+    source_path: String,
+    page_path: PathBuf,
+    modules: Vec<DocModule>,
+}
+
+impl DocFile {
+    fn new(program: &Program, file: &Arc<File>) -> Self {
+        let relative_path = relative_path(&program.root_path, &file.path);
+        Self {
+            source_path: slash_path(&relative_path),
+            page_path: relative_path.with_extension("md"),
+            modules: program
+                .sections
+                .iter()
+                .filter(|section| section.path() == file.path)
+                .sorted_unstable_by_key(|section| section.span.start)
+                .map(|section| DocModule::new(program, section))
+                .collect(),
+        }
+    }
+
+    fn content(&self) -> String {
+        let blocks = iter::once(format!("# `{}`", self.source_path))
+            .chain(
+                self.modules
+                    .iter()
+                    .flat_map(|module| module.blocks(&self.page_path)),
+            )
+            .join("\n\n");
+        format!("{blocks}\n")
+    }
+}
+
+#[derive(Debug)]
+struct DocModule {
+    kind: &'static str,
+    name: String,
+    doc: String,
+    imports: Vec<DocImport>,
+    items: Vec<DocItem>,
+}
+
+impl DocModule {
+    fn new(program: &Program, section: &Section) -> Self {
+        let file = program.files.get(section.path());
+        Self {
+            kind: match section.directive.kind() {
+                DirectiveKind::ComputeShader => "Compute shader",
+                DirectiveKind::RenderShader => "Render shader",
+                DirectiveKind::Mod
+                | DirectiveKind::Init
+                | DirectiveKind::Run
+                | DirectiveKind::Draw
+                | DirectiveKind::Import
+                | DirectiveKind::Toggle => "Module",
+            },
+            name: section.directive.section_name().slice.clone(),
+            doc: doc(&file.code[..section.span.start]),
+            imports: section
+                .directives()
+                .filter(|directive| directive.kind() == DirectiveKind::Import)
+                .map(|directive| {
+                    let (path, name) = directive.item_ident(&program.root_path);
+                    DocImport {
+                        page_path: relative_path(&program.root_path, &path).with_extension("md"),
+                        module_path: relative_path(&program.root_path, &path)
+                            .with_extension("")
+                            .join(&name),
+                        name,
+                    }
+                })
+                .collect(),
+            items: DocItem::extract(program, section),
+        }
+    }
+
+    fn anchor(&self) -> String {
+        anchor(&format!("{} `{}`", self.kind, self.name))
+    }
+
+    fn blocks(&self, page_path: &Path) -> Vec<String> {
+        let mut blocks = vec![format!("## {} `{}`", self.kind, self.name)];
+        if !self.doc.is_empty() {
+            blocks.push(self.doc.clone());
+        }
+        if !self.imports.is_empty() {
+            blocks.push(format!(
+                "Imports: {}",
+                self.imports
+                    .iter()
+                    .map(|import| format!(
+                        "[`{}`]({}#{})",
+                        slash_path(&import.module_path).replace('/', "."),
+                        relative_link(page_path, &import.page_path),
+                        anchor(&format!("Module `{}`", import.name))
+                    ))
+                    .join(", ")
+            ));
+        }
+        blocks.extend(self.items.iter().flat_map(DocItem::blocks));
+        blocks
+    }
+}
+
+#[derive(Debug)]
+struct DocImport {
+    page_path: PathBuf,
+    module_path: PathBuf,
+    name: String,
+}
+
+#[derive(Debug)]
+struct DocItem {
+    kind: &'static str,
+    name: String,
+    signature: String,
+    doc: String,
+    fields: Vec<DocField>,
+    size: Option<u32>,
+}
+
+impl DocItem {
+    fn extract(program: &Program, section: &Section) -> Vec<Self> {
+        let Some(module) = program.modules.get(&section.ident()) else {
+            return vec![];
+        };
+        let code = &program.files.get(section.path()).code;
+        let section_code = section.code();
+        let mut items = module.section_items();
+        items.extend(abstract_constants(section_code, &items));
+        items
+            .into_iter()
+            .filter(|item| !item.name.starts_with('_'))
+            .filter_map(|item| {
+                let span = match &item.span {
+                    Some(span) => span.clone(),
+                    None => declaration_span(section_code, item.kind, &item.name)?,
+                };
+                let span = section.span.start + span.start..section.span.start + span.end;
+                Some((span.start, Self::new(program, item, span, code)))
+            })
+            .sorted_unstable_by_key(|(start, _)| *start)
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    fn new(program: &Program, item: Item, span: Range<usize>, code: &str) -> Self {
+        let (attributes_start, attributes) = attributes(code, span.start);
+        let item_code = &code[span.clone()];
+        let fields = if item.kind == "struct" {
+            DocField::extract(&item.fields, span, code)
+        } else {
+            vec![]
+        };
+        let declaration = match item.kind {
+            "fn" => declaration_code(item_code.split('{').next().unwrap_or_default()),
+            "struct" => format!(
+                "struct {} {{\n{}}}",
+                item.name,
+                fields
+                    .iter()
+                    .map(|field| format!("    {},\n", field.signature))
+                    .join("")
+            ),
+            _ => declaration_code(item_code),
+        };
+        let size = (item.kind == "var")
+            .then(|| program.modules.storages.get(&item.name))
+            .flatten()
+            .map(|storage| storage.type_.size);
+        let signature = attributes
+            .into_iter()
+            .chain([declaration.as_str()])
+            .join("\n");
+        Self {
+            kind: item.kind,
+            signature,
+            doc: attributes_start.map_or_else(String::new, |start| doc(&code[..start])),
+            fields,
+            size,
+            name: item.name,
+        }
+    }
+
+    fn blocks(&self) -> Vec<String> {
+        let mut blocks = vec![
+            format!("### `{} {}`", self.kind, self.name),
+            format!("```wgsl\n{}\n```", self.signature),
+        ];
+        if !self.doc.is_empty() {
+            blocks.push(self.doc.clone());
+        }
+        if let Some(size) = self.size {
+            blocks.push(format!("Size: {size} bytes"));
+        }
+        if self.fields.iter().any(|field| !field.doc.is_empty()) {
+            blocks.push("Fields:".into());
+            blocks.push(
+                self.fields
+                    .iter()
+                    .map(|field| {
+                        if field.doc.is_empty() {
+                            format!("- `{}`", field.signature)
+                        } else {
+                            format!(
+                                "- `{}`: {}",
+                                field.signature,
+                                field
+                                    .doc
+                                    .lines()
+                                    .map(|line| if line.is_empty() {
+                                        String::new()
+                                    } else {
+                                        format!("  {line}")
+                                    })
+                                    .join("\n")
+                                    .trim_start()
+                            )
+                        }
+                    })
+                    .join("\n"),
+            );
+        }
+        blocks
+    }
+}
+
+#[derive(Debug)]
+struct DocField {
+    signature: String,
+    doc: String,
+}
+
+impl DocField {
+    /// Extracts the fields of the struct located at `struct_span`, in the order of `names`.
+    fn extract(names: &[String], struct_span: Range<usize>, code: &str) -> Vec<Self> {
+        let Some(body_offset) = code[struct_span.clone()].find('{') else {
+            return vec![];
+        };
+        let mut field_start = struct_span.start + body_offset + 1;
+        let mut fields = vec![];
+        for name in names {
+            let Some(name_start) = field_name_start(&code[..struct_span.end], field_start, name)
+            else {
+                break;
+            };
+            let type_end = field_type_end(&code[..struct_span.end], name_start);
+            if !name.starts_with('_') {
+                let attributes = code[field_start..name_start]
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| line.starts_with('@'));
+                let field_code = &code[name_start..type_end];
+                let declaration = field_code
+                    .find("/*")
+                    .or_else(|| field_code.find("//"))
+                    .map_or(field_code, |end| &field_code[..end])
+                    .split_whitespace()
+                    .join(" ");
+                let (doc_end, _) = self::attributes(code, name_start);
+                fields.push(Self {
+                    signature: attributes.chain([declaration.as_str()]).join(" "),
+                    doc: doc_end.map_or_else(String::new, |end| doc(&code[..end])),
+                });
+            }
+            field_start = type_end + 1;
+        }
+        fields
+    }
+}
+
+/// Returns the offset of the declaration of the field `name`, searched from `start` offset.
+///
+/// Occurrences located in comments are ignored.
+fn field_name_start(struct_code: &str, start: usize, name: &str) -> Option<usize> {
+    let mut search_start = start;
+    while let Some(offset) = struct_code[search_start..].find(name) {
+        let name_start = search_start + offset;
+        let line_start = struct_code[..name_start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let is_commented = struct_code[line_start..name_start].contains("//")
+            || struct_code[start..name_start].matches("/*").count()
+                > struct_code[start..name_start].matches("*/").count();
+        let is_ident = !struct_code[..name_start].ends_with(is_ident_char)
+            && struct_code[name_start + name.len()..]
+                .trim_start()
+                .starts_with(':');
+        if is_ident && !is_commented {
+            return Some(name_start);
+        }
+        search_start = name_start + name.len();
+    }
+    None
+}
+
+/// Returns the end offset of the type of the field declared at `name_start` offset.
+fn field_type_end(struct_code: &str, name_start: usize) -> usize {
+    let mut depth = 0;
+    for (index, char) in struct_code[name_start..].char_indices() {
+        match char {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' | '}' if depth == 0 => return name_start + index,
+            _ => (),
+        }
+    }
+    struct_code.len()
+}
+
+/// Returns the constants declared at the root of `code` and missing in the parsed `items`.
+///
+/// Constants with an abstract type (e.g. `const COUNT = 16;`) are not kept by naga, so they are
+/// found from the non-indented lines starting with `const`.
+fn abstract_constants(code: &str, items: &[Item]) -> Vec<Item> {
+    let mut line_start = 0;
+    let mut constants = vec![];
+    for line in code.split_inclusive('\n') {
+        let name: String = line
+            .strip_prefix("const ")
+            .unwrap_or_default()
+            .trim_start()
+            .chars()
+            .take_while(|&char| is_ident_char(char))
+            .collect();
+        let is_missing = !items
+            .iter()
+            .any(|item| item.kind == "const" && item.name == name);
+        if !name.is_empty() && is_missing {
+            constants.push(Item {
+                kind: "const",
+                span: declaration_span(&code[line_start..], "const", &name)
+                    .map(|span| line_start + span.start..line_start + span.end),
+                name,
+                fields: vec![],
+            });
+        }
+        line_start += line.len();
+    }
+    constants
+}
+
+/// Returns the span of the declaration of an item not located by naga.
+///
+/// The span ends after the first `;` or `{` character following the item name.
+fn declaration_span(code: &str, kind: &str, name: &str) -> Option<Range<usize>> {
+    let declaration = format!("{kind} {name}");
+    let start = code.match_indices(&declaration).find_map(|(start, _)| {
+        let line_start = code[..start].rfind('\n').map_or(0, |index| index + 1);
+        let is_commented = code[line_start..start].contains("//");
+        let is_ident = !code[..start].ends_with(is_ident_char)
+            && !code[start + declaration.len()..].starts_with(is_ident_char);
+        (is_ident && !is_commented).then_some(start)
+    })?;
+    let end = code[start..]
+        .find([';', '{'])
+        .map_or(code.len(), |offset| start + offset + 1);
+    Some(start..end)
+}
+
+/// Returns the `///` comment located just before `code_before` end.
+fn doc(code_before: &str) -> String {
+    code_before
+        .trim_end_matches([' ', '\t'])
+        .lines()
+        .rev()
+        .map(str::trim)
+        .take_while(|line| line.starts_with(DOC_COMMENT_PREFIX))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .filter_map(doc_line)
+        .join("\n")
+}
+
+/// Returns the attributes preceding the item starting at `item_start`, and the start offset
+/// of these attributes.
+///
+/// The offset is `None` if other code precedes the item on the same line.
+fn attributes(code: &str, item_start: usize) -> (Option<usize>, Vec<&str>) {
+    let mut start = code[..item_start].rfind('\n').map_or(0, |index| index + 1);
+    let inline_attributes = code[start..item_start].trim();
+    if !inline_attributes.is_empty() && !inline_attributes.starts_with('@') {
+        return (None, vec![]);
+    }
+    let mut attributes: Vec<_> = iter::once(inline_attributes)
+        .filter(|attributes| !attributes.is_empty())
+        .collect();
+    while start > 0 {
+        let line_start = code[..start - 1].rfind('\n').map_or(0, |index| index + 1);
+        let line = code[line_start..start - 1].trim();
+        if !line.starts_with('@') {
+            break;
+        }
+        attributes.insert(0, line);
+        start = line_start;
+    }
+    (Some(start), attributes)
+}
+
+fn doc_line(line: &str) -> Option<&str> {
+    line.trim()
+        .strip_prefix(DOC_COMMENT_PREFIX)
+        .map(|doc| doc.strip_prefix(' ').unwrap_or(doc).trim_end())
+}
+
+fn declaration_code(code: &str) -> String {
+    code.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .join("\n")
+}
+
+fn is_ident_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|char| match char {
+            ' ' => Some('-'),
+            char if char.is_alphanumeric() || char == '-' || char == '_' => Some(char),
+            _ => None,
+        })
+        .collect()
+}
+
+fn relative_path(root_path: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root_path).unwrap_or(path).to_path_buf()
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(segment.to_string_lossy()),
+            Component::Prefix(_)
+            | Component::RootDir
+            | Component::CurDir
+            | Component::ParentDir => None,
+        })
+        .join("/")
+}
+
+fn relative_link(from: &Path, to: &Path) -> String {
+    let depth = from.components().count().saturating_sub(1);
+    format!("{}{}", "../".repeat(depth), slash_path(to))
+}
@@ -31,8 +31,14 @@ pub enum Error {
     StorageConflict(PathBuf, PathBuf, String),
     /// WGSL code contains a feature unsupported by WGSO.
     UnsupportedWgslFeature(PathBuf, String),
-    /// Program cannot be reloaded because storage structure has changed.
-    ChangedStorageStructure,
+    /// A state provider is incompatible with the program storages.
+    InvalidStateProvider(String),
+    /// The program configuration in `wgso.yaml` is invalid.
+    InvalidConfig(PathBuf, String),
+    /// No GPU adapter matches the adapter options.
+    AdapterNotFound(String),
+    /// The operation is not supported by the runner.
+    UnsupportedOperation(String),
 }
 
 impl Error {
@@ -40,7 +46,10 @@ impl Error {
     pub fn render(&self, program: &Program) -> String {
         match self {
             Self::Io(path, error) => Self::io_message(path, error),
-            Self::WgpuValidation(error) => Self::wgpu_validation_message(error),
+            Self::WgpuValidation(error)
+            | Self::InvalidStateProvider(error)
+            | Self::AdapterNotFound(error)
+            | Self::UnsupportedOperation(error) => Self::wgpu_validation_message(error),
             Self::WgslParsing(sections, error) => {
                 Self::wgsl_parsing_message(program, sections, error)
             }
@@ -57,7 +66,7 @@ impl Error {
             Self::UnsupportedWgslFeature(path, message) => {
                 Self::unsupported_wgsl_feature_message(program, path, message)
             }
-            Self::ChangedStorageStructure => Self::changed_storage_structure_message(),
+            Self::InvalidConfig(path, message) => Self::invalid_config_message(path, message),
         }
     }
 
@@ -65,12 +74,77 @@ impl Error {
         match self {
             Self::Io(path, _) // no-coverage (not easy to test)
             | Self::StorageConflict(path, _, _)
-            | Self::UnsupportedWgslFeature(path, _) => Some(path),
+            | Self::UnsupportedWgslFeature(path, _)
+            | Self::InvalidConfig(path, _) => Some(path),
             Self::DirectiveParsing(error) => Some(&error.path),
             Self::WgslParsing(sections, error) => Some(Self::wgsl_parsing_error_path(sections, error)),
             Self::WgslValidation(sections, error) => Some(Self::wgsl_validation_error_path(sections, error)),
             Self::ModuleConflict(first, _) => Some(&first.path),
-            Self::WgpuValidation(_)|Self::ChangedStorageStructure => None, // no-coverage (never called in practice)
+            Self::WgpuValidation(_) | Self::InvalidStateProvider(_) | Self::AdapterNotFound(_) | Self::UnsupportedOperation(_) => None, // no-coverage (never called in practice)
+        }
+    }
+
+    pub(crate) fn locations(&self) -> Vec<ErrorLocation> {
+        match self {
+            Self::Io(path, error) => vec![ErrorLocation::new(path, 0..0, error.to_string())],
+            Self::WgslParsing(sections, error) => error
+                .labels()
+                .map(|(naga_span, _)| {
+                    let span = naga_span.to_range().unwrap_or(0..0);
+                    let (section, offset) = Self::merged_section(sections, span.start);
+                    ErrorLocation::new(
+                        section.path(),
+                        Self::section_to_file_span(span, section, offset),
+                        error.message().into(),
+                    )
+                })
+                .collect(),
+            Self::WgslValidation(sections, error) => error
+                .spans()
+                .map(|(naga_span, _)| {
+                    let span = naga_span.to_range().unwrap_or(0..0);
+                    let (section, offset) = Self::merged_section(sections, span.start);
+                    ErrorLocation::new(
+                        section.path(),
+                        Self::section_to_file_span(span, section, offset),
+                        error.to_string(),
+                    )
+                })
+                .collect(),
+            Self::DirectiveParsing(error) => vec![ErrorLocation::new(
+                &error.path,
+                error.span.clone(),
+                error.message.clone(),
+            )],
+            Self::ModuleConflict(first, second) => [first, second]
+                .into_iter()
+                .map(|token| {
+                    ErrorLocation::new(
+                        &token.path,
+                        token.span.clone(),
+                        format!("duplicated module name `{}`", token.slice),
+                    )
+                })
+                .collect(),
+            Self::StorageConflict(first, second, name) => [first, second]
+                .into_iter()
+                .map(|path| {
+                    ErrorLocation::new(
+                        path,
+                        0..0,
+                        format!("same name `{name}` used for two storage variables"),
+                    )
+                })
+                .collect(),
+            Self::UnsupportedWgslFeature(path, message) | Self::InvalidConfig(path, message) => {
+                vec![ErrorLocation::new(path, 0..0, message.clone())]
+            }
+            Self::WgpuValidation(_)
+            | Self::InvalidStateProvider(_)
+            | Self::AdapterNotFound(_)
+            | Self::UnsupportedOperation(_) => {
+                vec![]
+            }
         }
     }
 
@@ -128,6 +202,17 @@ impl Error {
         )
     }
 
+    fn invalid_config_message(path: &Path, message: &str) -> String {
+        format!(
+            "{}",
+            Renderer::styled().render(
+                Level::Error
+                    .title(&format!("invalid configuration in '{}'", path.display()))
+                    .footer(Level::Info.title(message))
+            )
+        )
+    }
+
     fn wgsl_parsing_message(
         program: &Program,
         sections: &[Arc<Section>],
@@ -189,7 +274,7 @@ impl Error {
         let source = error::Error::source(error.as_inner()).map(ToString::to_string);
         if let Some(source) = &source {
             message = message.footer(Level::Info.title(source));
-        };
+        }
         for (label, span, path, path_str) in &paths {
             message = message.snippet(
                 Snippet::source(&program.files.get(path).code)
@@ -299,14 +384,21 @@ impl Error {
             )
         )
     }
+}
 
-    fn changed_storage_structure_message() -> String {
-        format!(
-            "{}",
-            Renderer::styled().render(
-                Level::Error
-                    .title("program cannot be hot-reloaded because storages have been changed")
-            )
-        )
+#[derive(Debug)]
+pub(crate) struct ErrorLocation {
+    pub(crate) path: PathBuf,
+    pub(crate) span: Range<usize>,
+    pub(crate) message: String,
+}
+
+impl ErrorLocation {
+    fn new(path: &Path, span: Range<usize>, message: String) -> Self {
+        Self {
+            path: path.into(),
+            span,
+            message,
+        }
     }
 }
@@ -0,0 +1,284 @@
+use crate::program::plan::{ExecutionKind, ExecutionPlan, ExecutionStep};
+use crate::program::reflection::SectionKind;
+use crate::{program, Program};
+use itertools::Itertools;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::iter;
+
+/// A graph describing the dependencies between the items of a WGSO program.
+#[derive(Debug, Serialize)]
+pub(crate) struct Graph {
+    sections: Vec<GraphSection>,
+    storages: Vec<GraphStorage>,
+    imports: Vec<GraphImport>,
+    calls: Vec<GraphCall>,
+    toggles: Vec<GraphToggle>,
+    accesses: Vec<GraphAccess>,
+}
+
+impl Graph {
+    pub(crate) fn new(program: &Program) -> Self {
+        let sections = program.sections();
+        let plan = ExecutionPlan::new(program);
+        Self {
+            imports: sections
+                .iter()
+                .flat_map(|section| {
+                    section.imports.iter().map(|import| GraphImport {
+                        from: section.id.clone(),
+                        to: import.clone(),
+                    })
+                })
+                .collect(),
+            sections: sections
+                .into_iter()
+                .map(|section| GraphSection {
+                    id: section.id,
+                    kind: match section.kind {
+                        SectionKind::Mod => GraphSectionKind::Mod,
+                        SectionKind::ComputeShader => GraphSectionKind::Compute,
+                        SectionKind::RenderShader => GraphSectionKind::Render,
+                    },
+                })
+                .collect(),
+            storages: program
+                .storages()
+                .into_iter()
+                .map(|storage| GraphStorage {
+                    name: storage.name,
+                    type_: storage.type_.label,
+                    size: storage.type_.size,
+                })
+                .collect(),
+            calls: plan
+                .compute_steps
+                .into_iter()
+                .chain(plan.render_steps)
+                .map(GraphCall::new)
+                .collect(),
+            toggles: program
+                .toggles()
+                .into_iter()
+                .map(|toggle| GraphToggle {
+                    storage: toggle.buffer,
+                    prefix: toggle.prefix,
+                    sections: toggle.sections,
+                })
+                .collect(),
+            accesses: program
+                .modules
+                .compute
+                .iter()
+                .chain(&program.modules.render)
+                .sorted_unstable_by_key(|(ident, _)| *ident)
+                .flat_map(|((path, name), module)| {
+                    let shader_id =
+                        format!("{}.{name}", program::dotted_path(&program.root_path, path));
+                    module
+                        .used_storage_bindings()
+                        .sorted_unstable_by_key(|(storage, _)| *storage)
+                        .map(move |(storage, binding)| GraphAccess {
+                            shader: shader_id.clone(),
+                            storage: storage.clone(),
+                            access: if binding.is_read_only {
+                                GraphAccessKind::Read
+                            } else {
+                                GraphAccessKind::ReadWrite
+                            },
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("internal error: graph should be serializable")
+    }
+
+    pub(crate) fn to_dot(&self) -> String {
+        let sections = self.sections.iter().map(|section| {
+            let (label, style) = match section.kind {
+                GraphSectionKind::Mod => ("#mod", "solid"),
+                GraphSectionKind::Compute => ("#shader<compute>", "bold"),
+                GraphSectionKind::Render => ("#shader<render>", "bold"),
+            };
+            format!(
+                "    {} [label={}, shape=box, style={style}];",
+                quoted(&section.id),
+                quoted(&format!("{label} {}", section.id))
+            )
+        });
+        let storages = self.storages.iter().map(|storage| {
+            format!(
+                "    {} [label={}, shape=ellipse];",
+                quoted(&storage_id(&storage.name)),
+                quoted(&format!("{}: {}", storage.name, storage.type_))
+            )
+        });
+        let imports = self.imports.iter().map(|import| {
+            format!(
+                "    {} -> {} [label=\"import\", style=dashed];",
+                quoted(&import.from),
+                quoted(&import.to)
+            )
+        });
+        let calls = self.calls.iter().map(|call| {
+            format!(
+                "    {} -> {} [label={}, color=blue];",
+                quoted(&call.from),
+                quoted(&call.to),
+                quoted(
+                    &iter::once(format!(
+                        "#{} priority={}",
+                        call.kind.as_str(),
+                        call.priority
+                    ))
+                    .chain(
+                        call.args
+                            .iter()
+                            .map(|(name, value)| format!("{name}={value}"))
+                    )
+                    .join(" ")
+                )
+            )
+        });
+        let toggles = self.toggles.iter().flat_map(|toggle| {
+            let storage = toggle.storage.split('.').next().unwrap_or_default();
+            toggle.sections.iter().map(move |section| {
+                format!(
+                    "    {} -> {} [label={}, style=dotted, color=darkgreen];",
+                    quoted(&storage_id(storage)),
+                    quoted(section),
+                    quoted(&format!("#toggle<{}>", toggle.storage))
+                )
+            })
+        });
+        let accesses = self.accesses.iter().map(|access| match access.access {
+            GraphAccessKind::Read => format!(
+                "    {} -> {} [label=\"read\", color=gray];",
+                quoted(&storage_id(&access.storage)),
+                quoted(&access.shader)
+            ),
+            GraphAccessKind::ReadWrite => format!(
+                "    {} -> {} [label=\"read_write\", color=red, dir=both];",
+                quoted(&access.shader),
+                quoted(&storage_id(&access.storage))
+            ),
+        });
+        let lines = sections
+            .chain(storages)
+            .chain(imports)
+            .chain(calls)
+            .chain(toggles)
+            .chain(accesses)
+            .join("\n");
+        format!("digraph wgso {{\n{lines}\n}}\n")
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GraphSection {
+    id: String,
+    kind: GraphSectionKind,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum GraphSectionKind {
+    Mod,
+    Compute,
+    Render,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphStorage {
+    name: String,
+    #[serde(rename = "type")]
+    type_: String,
+    size: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphImport {
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphCall {
+    from: String,
+    to: String,
+    kind: GraphCallKind,
+    priority: i32,
+    args: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    vertex_buffer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance_buffer: Option<String>,
+}
+
+impl GraphCall {
+    fn new(step: ExecutionStep) -> Self {
+        Self {
+            from: step.caller,
+            to: step.shader,
+            kind: match step.kind {
+                ExecutionKind::Init => GraphCallKind::Init,
+                ExecutionKind::Run => GraphCallKind::Run,
+                ExecutionKind::Draw => GraphCallKind::Draw,
+            },
+            priority: step.priority,
+            args: step.args.into_iter().collect(),
+            vertex_buffer: step.vertex_buffer,
+            instance_buffer: step.instance_buffer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum GraphCallKind {
+    Init,
+    Run,
+    Draw,
+}
+
+impl GraphCallKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Init => "init",
+            Self::Run => "run",
+            Self::Draw => "draw",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GraphToggle {
+    storage: String,
+    prefix: String,
+    sections: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphAccess {
+    shader: String,
+    storage: String,
+    access: GraphAccessKind,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum GraphAccessKind {
+    Read,
+    ReadWrite,
+}
+
+fn storage_id(name: &str) -> String {
+    format!("storage:{name}")
+}
+
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -2,13 +2,25 @@
 #![allow(clippy::result_large_err)]
 
 mod cli;
+mod config;
 mod directives;
+mod doc;
 mod error;
+mod graph;
+mod lsp;
 mod program;
 mod runner;
 
 pub use cli::*;
 pub use error::*;
+pub use lsp::*;
 pub use program::file::*;
+pub use program::plan::*;
+pub use program::reflection::*;
 pub use program::*;
+pub use runner::adapter::*;
+pub use runner::event::*;
+pub use runner::gamepad::*;
+pub use runner::migration::*;
+pub use runner::provider::*;
 pub use runner::*;
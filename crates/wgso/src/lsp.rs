@@ -0,0 +1,355 @@
+use crate::directives::defs::DEF_DIRECTIVE_KINDS;
+use crate::directives::DirectiveKind;
+use crate::Program;
+use fxhash::{FxHashMap, FxHashSet};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidOpenTextDocument, DidSaveTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticSeverity, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, InitializeParams, Location, MarkupContent, MarkupKind, OneOf,
+    Position, PublishDiagnosticsParams, Range, SaveOptions, ServerCapabilities,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+use std::error::Error;
+use std::fs;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+/// A language server providing editor support for WGSO programs.
+///
+/// The server provides:
+/// - diagnostics published when a file is opened or saved,
+/// - go-to-definition for `#import`, `#init`, `#run` and `#draw` item paths,
+/// - completion of storage buffer and field names,
+/// - hover information about storage buffers and fields.
+pub struct LanguageServer {
+    connection: Connection,
+    root_path: PathBuf,
+    program: Option<Program>,
+    diagnostic_paths: FxHashSet<PathBuf>,
+}
+
+impl LanguageServer {
+    /// Creates a new language server communicating through a `connection`.
+    ///
+    /// The program folder is retrieved from the client during initialization.
+    /// If the client provides no folder, `default_root_path` is used.
+    pub fn new(connection: Connection, default_root_path: impl Into<PathBuf>) -> Self {
+        Self {
+            connection,
+            root_path: default_root_path.into(),
+            program: None,
+            diagnostic_paths: FxHashSet::default(),
+        }
+    }
+
+    /// Runs the server until the client requests a shutdown.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned in case of communication issue with the client.
+    pub fn run(mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let capabilities = serde_json::to_value(Self::capabilities())?;
+        let params: InitializeParams =
+            serde_json::from_value(self.connection.initialize(capabilities)?)?;
+        let root_path = Self::root_path(&params).unwrap_or_else(|| self.root_path.clone());
+        self.root_path = fs::canonicalize(&root_path).unwrap_or(root_path);
+        self.refresh()?;
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => {
+                    self.handle_notification(&notification)?;
+                }
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(TextDocumentSyncKind::NONE),
+                    save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                        include_text: Some(false),
+                    })),
+                    ..TextDocumentSyncOptions::default()
+                },
+            )),
+            definition_provider: Some(OneOf::Left(true)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            completion_provider: Some(CompletionOptions {
+                trigger_characters: Some(vec![".".into()]),
+                ..CompletionOptions::default()
+            }),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    #[allow(deprecated)]
+    fn root_path(params: &InitializeParams) -> Option<PathBuf> {
+        params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| &folder.uri)
+            .or(params.root_uri.as_ref())
+            .and_then(|uri| uri.to_file_path().ok())
+    }
+
+    fn handle_request(&self, request: Request) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let id = request.id.clone();
+        match request.method.as_str() {
+            GotoDefinition::METHOD => {
+                let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+                let result = self.definition(&params.text_document_position_params);
+                self.respond(id, result)
+            }
+            HoverRequest::METHOD => {
+                let params: HoverParams = serde_json::from_value(request.params)?;
+                let result = self.hover(&params.text_document_position_params);
+                self.respond(id, result)
+            }
+            Completion::METHOD => {
+                let params: CompletionParams = serde_json::from_value(request.params)?;
+                let result = self.completion(&params.text_document_position);
+                self.respond(id, result)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn handle_notification(
+        &mut self,
+        notification: &Notification,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let _: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params.clone())?;
+                self.refresh()
+            }
+            DidSaveTextDocument::METHOD => {
+                let _: DidSaveTextDocumentParams =
+                    serde_json::from_value(notification.params.clone())?;
+                self.refresh()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn respond(
+        &self,
+        id: RequestId,
+        result: impl serde::Serialize,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let response = Response::new_ok(id, result);
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn refresh(&mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let program = Program::parse(self.root_path.as_path());
+        let mut diagnostics: FxHashMap<PathBuf, Vec<Diagnostic>> = FxHashMap::default();
+        for file in program.files.iter() {
+            diagnostics.insert(file.path.clone(), vec![]);
+        }
+        for location in program.errors.iter().flat_map(crate::Error::locations) {
+            let code = Self::code(&program, &location.path);
+            diagnostics
+                .entry(location.path)
+                .or_default()
+                .push(Diagnostic {
+                    range: Range {
+                        start: position(&code, location.span.start),
+                        end: position(&code, location.span.end),
+                    },
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("wgso".into()),
+                    message: location.message,
+                    ..Diagnostic::default()
+                });
+        }
+        for path in mem::take(&mut self.diagnostic_paths) {
+            diagnostics.entry(path).or_default();
+        }
+        for (path, diagnostics) in diagnostics {
+            let Some(uri) = Self::uri(&path) else {
+                continue;
+            };
+            if !diagnostics.is_empty() {
+                self.diagnostic_paths.insert(path);
+            }
+            let params = PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            };
+            self.connection
+                .sender
+                .send(Message::Notification(Notification::new(
+                    PublishDiagnostics::METHOD.into(),
+                    params,
+                )))?;
+        }
+        self.program = Some(program);
+        Ok(())
+    }
+
+    fn definition(&self, params: &TextDocumentPositionParams) -> Option<GotoDefinitionResponse> {
+        let program = self.program.as_ref()?;
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let file = program.files.find(&path)?;
+        let offset = offset(&file.code, params.position);
+        let directive = file.directives.iter().find(|directive| {
+            [
+                DirectiveKind::Import,
+                DirectiveKind::Init,
+                DirectiveKind::Run,
+                DirectiveKind::Draw,
+            ]
+            .contains(&directive.kind())
+                && directive.item_span().start <= offset
+                && offset <= directive.item_span().end
+        })?;
+        let (item_path, item_name) = directive.item_ident(&program.root_path);
+        let item_file = program.files.find(&item_path)?;
+        let item_directive = item_file.directives.iter().find(|directive| {
+            DEF_DIRECTIVE_KINDS.contains(&directive.kind())
+                && directive.section_name().slice == item_name
+        })?;
+        let span = &item_directive.section_name().span;
+        Some(GotoDefinitionResponse::Scalar(Location {
+            uri: Self::uri(&item_path)?,
+            range: Range {
+                start: position(&item_file.code, span.start),
+                end: position(&item_file.code, span.end),
+            },
+        }))
+    }
+
+    fn hover(&self, params: &TextDocumentPositionParams) -> Option<Hover> {
+        let program = self.program.as_ref()?;
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let code = Self::code(program, &path);
+        let offset = offset(&code, params.position);
+        let start = code[..offset]
+            .rfind(|char: char| !is_ident_char(char) && char != '.')
+            .map_or(0, |index| index + 1);
+        let end = code[offset..]
+            .find(|char: char| !is_ident_char(char))
+            .map_or(code.len(), |index| offset + index);
+        let field_path = &code[start..end];
+        let field = program.parse_field(field_path)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!(
+                    "```wgsl\n{field_path}: {}\n```\nsize: {} bytes, offset: {} bytes",
+                    field.type_.label, field.type_.size, field.type_.offset
+                ),
+            }),
+            range: Some(Range {
+                start: position(&code, start),
+                end: position(&code, end),
+            }),
+        })
+    }
+
+    fn completion(&self, params: &TextDocumentPositionParams) -> Option<CompletionResponse> {
+        let program = self.program.as_ref()?;
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let code = Self::code(program, &path);
+        let offset = offset(&code, params.position);
+        let start = code[..offset]
+            .rfind(|char: char| !is_ident_char(char) && char != '.')
+            .map_or(0, |index| index + 1);
+        let segments: Vec<_> = code[start..offset].split('.').collect();
+        let items = if let [_] = segments[..] {
+            program
+                .modules
+                .storages
+                .iter()
+                .map(|(name, storage)| CompletionItem {
+                    label: name.clone(),
+                    kind: Some(CompletionItemKind::VARIABLE),
+                    detail: Some(storage.type_.label.clone()),
+                    ..CompletionItem::default()
+                })
+                .collect()
+        } else {
+            let storage = program.modules.storages.get(segments[0])?;
+            storage
+                .type_
+                .field_name_type(&segments[1..segments.len() - 1])?
+                .fields
+                .iter()
+                .map(|field| CompletionItem {
+                    label: field.name.clone(),
+                    kind: Some(CompletionItemKind::FIELD),
+                    detail: Some(field.type_.label.clone()),
+                    ..CompletionItem::default()
+                })
+                .collect()
+        };
+        Some(CompletionResponse::Array(items))
+    }
+
+    fn code(program: &Program, path: &Path) -> String {
+        program.files.find(path).map_or_else(
+            || fs::read_to_string(path).unwrap_or_default(),
+            |file| file.code.clone(),
+        )
+    }
+
+    fn uri(path: &Path) -> Option<Url> {
+        let path = fs::canonicalize(path).ok()?;
+        Url::from_file_path(path).ok()
+    }
+}
+
+fn is_ident_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+fn offset(code: &str, position: Position) -> usize {
+    let line_offset: usize = code
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    let line = code[line_offset..].lines().next().unwrap_or("");
+    let mut utf16_offset = 0;
+    for (index, char) in line.char_indices() {
+        if utf16_offset >= position.character as usize {
+            return line_offset + index;
+        }
+        utf16_offset += char.len_utf16();
+    }
+    line_offset + line.len()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn position(code: &str, offset: usize) -> Position {
+    let offset = offset.min(code.len());
+    let before = &code[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].encode_utf16().count() as u32,
+    }
+}
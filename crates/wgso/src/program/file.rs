@@ -1,9 +1,11 @@
+use crate::config::ProgramConfig;
 use crate::directives::Directive;
 use crate::Error;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
 use std::ffi::OsStr;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::vec::IntoIter;
@@ -36,6 +38,10 @@ impl Files {
         &self.files[path]
     }
 
+    pub(crate) fn find(&self, path: &Path) -> Option<&Arc<File>> {
+        self.files.get(path)
+    }
+
     pub(crate) fn exists(&self, path: &Path) -> bool {
         self.files.contains_key(path)
     }
@@ -48,6 +54,69 @@ impl Files {
             .collect()
     }
 
+    /// Reads the files of a folder, reusing previously parsed files that have not changed.
+    pub(crate) fn reload(
+        &self,
+        folder_path: &Path,
+        changed_paths: &FxHashSet<PathBuf>,
+        directive_rules: &[Rule],
+        errors: &mut Vec<Error>,
+    ) -> Self {
+        let files = Self::read_folder(folder_path, directive_rules, errors, |path| {
+            (!changed_paths.contains(path))
+                .then(|| self.files.get(path).cloned())
+                .flatten()
+        });
+        let directives = Self::directives(&files);
+        Self { files, directives }
+    }
+
+    /// Returns whether the file at `path` is the same in both file sets.
+    pub(crate) fn is_unchanged(&self, previous: &Self, path: &Path) -> bool {
+        self.files
+            .get(path)
+            .zip(previous.files.get(path))
+            .is_some_and(|(file, previous_file)| Arc::ptr_eq(file, previous_file))
+    }
+
+    fn read_folder(
+        folder_path: &Path,
+        directive_rules: &[Rule],
+        errors: &mut Vec<Error>,
+        cached_file: impl Fn(&Path) -> Option<Arc<File>>,
+    ) -> FxHashMap<PathBuf, Arc<File>> {
+        walkdir::WalkDir::new(folder_path)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|file| match file {
+                Ok(file) => {
+                    if Self::is_wgsl_file(&file) {
+                        if let Some(cached_file) = cached_file(file.path()) {
+                            return Some((cached_file.path.clone(), cached_file));
+                        }
+                        match fs::read_to_string(file.path()) {
+                            Ok(code) => Some(File::new(code, file.path(), directive_rules, errors)),
+                            // coverage: off (not easy to test)
+                            Err(error) => {
+                                errors.push(Error::Io(file.path().into(), error));
+                                None
+                            } // coverage: on
+                        }
+                        .map(|file| (file.path.clone(), Arc::new(file)))
+                    } else {
+                        None
+                    }
+                }
+                Err(error) => {
+                    if let Some(error) = error.into_io_error() {
+                        errors.push(Error::Io(folder_path.into(), error));
+                    }
+                    None
+                }
+            })
+            .collect()
+    }
+
     fn is_wgsl_file(file: &walkdir::DirEntry) -> bool {
         !file.file_type().is_dir() && file.path().extension() == Some(OsStr::new("wgsl"))
     }
@@ -107,6 +176,17 @@ pub trait SourceFolder: Clone {
 
     /// Returns folder path.
     fn path(&self) -> PathBuf;
+
+    /// Returns the content of the `wgso.yaml` file located at the root of the folder, if any.
+    ///
+    /// By default, the folder has no configuration file.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the file exists but cannot be read.
+    fn config(&self) -> io::Result<Option<String>> {
+        Ok(None)
+    }
 }
 
 impl SourceFolder for &Path {
@@ -116,38 +196,21 @@ impl SourceFolder for &Path {
         directive_rules: &[Rule],
         errors: &mut Vec<Error>,
     ) -> FxHashMap<PathBuf, Arc<File>> {
-        walkdir::WalkDir::new(self)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|file| match file {
-                Ok(file) => {
-                    if Files::is_wgsl_file(&file) {
-                        match fs::read_to_string(file.path()) {
-                            Ok(code) => Some(File::new(code, file.path(), directive_rules, errors)),
-                            // coverage: off (not easy to test)
-                            Err(error) => {
-                                errors.push(Error::Io(file.path().into(), error));
-                                None
-                            } // coverage: on
-                        }
-                        .map(|file| (file.path.clone(), Arc::new(file)))
-                    } else {
-                        None
-                    }
-                }
-                Err(error) => {
-                    if let Some(error) = error.into_io_error() {
-                        errors.push(Error::Io(self.into(), error));
-                    }
-                    None
-                }
-            })
-            .collect()
+        Files::read_folder(self, directive_rules, errors, |_| None)
     }
 
     fn path(&self) -> PathBuf {
         self.into()
     }
+
+    fn config(&self) -> io::Result<Option<String>> {
+        let path = ProgramConfig::path(self);
+        if path.is_file() {
+            fs::read_to_string(path).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 // coverage: off (not used on native platforms)
@@ -187,5 +250,11 @@ impl SourceFolder for include_dir::Dir<'_> {
     fn path(&self) -> PathBuf {
         include_dir::Dir::path(self).into()
     }
+
+    fn config(&self) -> io::Result<Option<String>> {
+        Ok(self
+            .get_file(ProgramConfig::path(include_dir::Dir::path(self)))
+            .map(|file| String::from_utf8_lossy(file.contents()).into()))
+    }
 }
 // coverage: on
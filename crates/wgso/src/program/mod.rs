@@ -1,17 +1,23 @@
+use crate::config::ProgramConfig;
 use crate::program::file::SourceFolder;
+use crate::program::plan::ExecutionPlan;
+use crate::program::reflection::{CallSite, SectionInfo, ShaderInfo, StorageInfo, ToggleInfo};
 use crate::program::section::Sections;
 use crate::program::type_::Type;
 use crate::{directives, Error};
 use file::Files;
+use fxhash::FxHashSet;
 use itertools::Itertools;
 use module::Modules;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 pub(crate) mod file;
 pub(crate) mod module;
+pub(crate) mod plan;
+pub(crate) mod reflection;
 pub(crate) mod section;
 pub(crate) mod type_;
-mod wgsl;
+pub(crate) mod wgsl;
 
 /// A parsed WGSO program.
 #[derive(Debug)]
@@ -19,6 +25,7 @@ pub struct Program {
     /// The errors found during parsing.
     pub errors: Vec<Error>,
     pub(crate) root_path: PathBuf,
+    pub(crate) config: ProgramConfig,
     pub(crate) files: Files,
     pub(crate) sections: Sections,
     pub(crate) modules: Modules,
@@ -34,15 +41,58 @@ impl Program {
             .join("\n")
     }
 
-    pub(crate) fn parse(source: impl SourceFolder) -> Self {
+    /// Parses a WGSO program.
+    ///
+    /// Parsing errors are stored in [`Program::errors`].
+    pub fn parse(source: impl SourceFolder) -> Self {
         let root_path = source.path();
         let mut errors = vec![];
+        let config = ProgramConfig::load(&source).unwrap_or_else(|error| {
+            errors.push(error);
+            ProgramConfig::default()
+        });
         let directive_rules = directives::load_rules();
         let files = Files::new(source, &directive_rules, &mut errors);
+        Self::new(root_path, config, files, errors, None)
+    }
+
+    /// Parses again a program, only reading files in `changed_paths`.
+    ///
+    /// Modules whose imported sections are all in unchanged files are reused.
+    /// If the configuration file has changed, the whole program is parsed again.
+    pub(crate) fn reload(&self, changed_paths: &FxHashSet<PathBuf>) -> Self {
+        if changed_paths.contains(&ProgramConfig::path(&self.root_path)) {
+            return Self::parse(self.root_path.as_path());
+        }
+        let mut errors = vec![];
+        let directive_rules = directives::load_rules();
+        let files = self.files.reload(
+            &self.root_path,
+            changed_paths,
+            &directive_rules,
+            &mut errors,
+        );
+        Self::new(
+            self.root_path.clone(),
+            self.config.clone(),
+            files,
+            errors,
+            Some(self),
+        )
+    }
+
+    fn new(
+        root_path: PathBuf,
+        config: ProgramConfig,
+        files: Files,
+        mut errors: Vec<Error>,
+        previous: Option<&Self>,
+    ) -> Self {
         if !errors.is_empty() {
             return Self {
                 errors,
                 root_path,
+                config,
                 files,
                 sections: Sections::default(),
                 modules: Modules::default(),
@@ -57,16 +107,26 @@ impl Program {
             return Self {
                 errors,
                 root_path,
+                config,
                 files,
                 sections,
                 modules: Modules::default(),
             };
         }
-        let modules = Modules::new(&root_path, &sections, &mut errors);
+        let previous_modules = previous.map(|previous| (&previous.modules, &previous.files));
+        let modules = Modules::new(
+            &root_path,
+            &files,
+            &sections,
+            previous_modules,
+            config.capabilities(),
+            &mut errors,
+        );
         if !errors.is_empty() {
             return Self {
                 errors,
                 root_path,
+                config,
                 files,
                 sections,
                 modules,
@@ -78,21 +138,102 @@ impl Program {
             return Self {
                 errors,
                 root_path,
+                config,
                 files,
                 sections,
                 modules,
             };
         }
         directives::toggle::check(&sections, &modules, &root_path, &mut errors);
+        config.check_toggles(&sections, &root_path, &mut errors);
         Self {
             errors,
             root_path,
+            config,
             files,
             sections,
             modules,
         }
     }
 
+    /// Returns the ordered list of shader executions of the program.
+    ///
+    /// # Errors
+    ///
+    /// The errors of the program are returned if parsing has failed.
+    pub fn execution_plan(&self) -> Result<ExecutionPlan, &[Error]> {
+        if self.errors.is_empty() {
+            Ok(ExecutionPlan::new(self))
+        } else {
+            Err(&self.errors)
+        }
+    }
+
+    /// Returns the path of the program folder.
+    pub fn root_path(&self) -> &Path {
+        &self.root_path
+    }
+
+    /// Returns the paths of all parsed WGSL files, sorted alphabetically.
+    pub fn file_paths(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|file| file.path.clone()).collect()
+    }
+
+    /// Returns all sections of the program, sorted by path and name.
+    pub fn sections(&self) -> Vec<SectionInfo> {
+        self.sections
+            .iter()
+            .map(|section| SectionInfo::new(self, section))
+            .collect()
+    }
+
+    /// Returns all storage buffers of the program, sorted by name.
+    ///
+    /// The storages are only available if the program contains no error.
+    pub fn storages(&self) -> Vec<StorageInfo> {
+        self.modules
+            .storages
+            .iter()
+            .sorted_unstable_by_key(|(name, _)| *name)
+            .map(|(name, storage)| StorageInfo::new(self, name, storage))
+            .collect()
+    }
+
+    /// Returns all compute and render shaders of the program, sorted by identifier.
+    ///
+    /// The shaders are only available if the program contains no error.
+    pub fn shaders(&self) -> Vec<ShaderInfo> {
+        self.modules
+            .compute
+            .values()
+            .chain(self.modules.render.values())
+            .map(|module| ShaderInfo::new(self, module))
+            .sorted_unstable_by(|shader1, shader2| shader1.id.cmp(&shader2.id))
+            .collect()
+    }
+
+    /// Returns all toggles of the program, sorted by location.
+    pub fn toggles(&self) -> Vec<ToggleInfo> {
+        self.sections
+            .toggle_directives()
+            .sorted_unstable_by_key(|directive| (directive.path(), directive.span().start))
+            .map(|directive| ToggleInfo::new(self, directive))
+            .collect()
+    }
+
+    /// Returns all `#init`, `#run` and `#draw` directives of the program, sorted by location.
+    pub fn call_sites(&self) -> Vec<CallSite> {
+        self.sections
+            .run_directives()
+            .chain(self.sections.draw_directives())
+            .map(|(directive, section)| CallSite::new(self, directive, section))
+            .sorted_unstable_by(|call1, call2| {
+                (&call1.directive.path, call1.directive.span.start)
+                    .cmp(&(&call2.directive.path, call2.directive.span.start))
+            })
+            .collect()
+    }
+
     pub(crate) fn with_sorted_errors(mut self) -> Self {
         self.errors
             .sort_unstable_by_key(|e| e.path().map(Path::to_path_buf));
@@ -115,3 +256,18 @@ pub(crate) struct StorageField<'a> {
     pub(crate) buffer_name: String,
     pub(crate) type_: &'a Type,
 }
+
+pub(crate) fn dotted_path(root_path: &Path, path: &Path) -> String {
+    path.with_extension("")
+        .strip_prefix(root_path)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(segment.to_string_lossy()),
+            Component::Prefix(_)
+            | Component::RootDir
+            | Component::CurDir
+            | Component::ParentDir => None,
+        })
+        .join(".")
+}
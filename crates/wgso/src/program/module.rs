@@ -1,11 +1,13 @@
 use crate::directives::{Directive, DirectiveKind};
+use crate::program::file::Files;
 use crate::program::section::{Section, Sections};
 use crate::program::type_;
 use crate::program::type_::Type;
-use crate::program::wgsl::{Binding, BindingKind, WgslModule};
+use crate::program::wgsl::{Binding, BindingKind, Item, WgslModule};
 use crate::Error;
 use fxhash::{FxHashMap, FxHashSet};
 use itertools::Itertools;
+use naga::valid::{Capabilities, ModuleInfo};
 use std::collections::hash_map::Entry;
 use std::iter;
 use std::path::{Path, PathBuf};
@@ -16,17 +18,38 @@ pub(crate) struct Modules {
     pub(crate) storages: FxHashMap<String, Storage>,
     pub(crate) compute: FxHashMap<(PathBuf, String), Arc<Module>>,
     pub(crate) render: FxHashMap<(PathBuf, String), Arc<Module>>,
+    all: FxHashMap<(PathBuf, String), Arc<Module>>,
 }
 
 impl Modules {
-    pub(crate) fn new(root_path: &Path, sections: &Sections, errors: &mut Vec<Error>) -> Self {
+    /// Creates the modules of all sections.
+    ///
+    /// If previous modules are provided, the ones whose imported sections all come from unchanged
+    /// files are reused instead of being parsed again.
+    pub(crate) fn new(
+        root_path: &Path,
+        files: &Files,
+        sections: &Sections,
+        previous: Option<(&Self, &Files)>,
+        capabilities: Capabilities,
+        errors: &mut Vec<Error>,
+    ) -> Self {
         let modules = sections
             .iter()
-            .filter_map(|section| match Module::new(root_path, section, sections) {
-                Ok(module) => Some(Arc::new(module)),
-                Err(error) => {
-                    errors.push(error);
-                    None
+            .filter_map(|section| {
+                if let Some(module) = previous.and_then(|(previous_modules, previous_files)| {
+                    previous_modules.reusable_module(root_path, section, sections, |path| {
+                        files.is_unchanged(previous_files, path)
+                    })
+                }) {
+                    return Some(module);
+                }
+                match Module::new(root_path, section, sections, capabilities) {
+                    Ok(module) => Some(Arc::new(module)),
+                    Err(error) => {
+                        errors.push(error);
+                        None
+                    }
                 }
             })
             .collect::<Vec<_>>();
@@ -34,11 +57,33 @@ impl Modules {
             storages: Self::storages(&modules, errors),
             compute: Self::shaders(&modules, DirectiveKind::ComputeShader),
             render: Self::shaders(&modules, DirectiveKind::RenderShader),
+            all: modules
+                .iter()
+                .map(|module| (module.section.ident(), module.clone()))
+                .collect(),
         };
         modules.configure_storages(root_path, sections);
         modules
     }
 
+    pub(crate) fn get(&self, ident: &(PathBuf, String)) -> Option<&Arc<Module>> {
+        self.all.get(ident)
+    }
+
+    fn reusable_module(
+        &self,
+        root_path: &Path,
+        section: &Arc<Section>,
+        sections: &Sections,
+        is_unchanged: impl Fn(&Path) -> bool,
+    ) -> Option<Arc<Module>> {
+        Module::extract_section_idents(root_path, section, sections)
+            .iter()
+            .all(|(path, _)| is_unchanged(path))
+            .then(|| self.all.get(&section.ident()).cloned())
+            .flatten()
+    }
+
     fn storages(modules: &[Arc<Module>], errors: &mut Vec<Error>) -> FxHashMap<String, Storage> {
         let mut storages = FxHashMap::default();
         for module in modules {
@@ -119,6 +164,7 @@ impl Modules {
 pub(crate) struct Module {
     pub(crate) code: String,
     wgsl: WgslModule,
+    info: ModuleInfo,
     types: FxHashMap<String, Type>,
     bindings: FxHashMap<String, Binding>,
     section: Arc<Section>,
@@ -129,15 +175,18 @@ impl Module {
         root_path: &Path,
         section: &Arc<Section>,
         sections: &Sections,
+        capabilities: Capabilities,
     ) -> Result<Self, Error> {
         let (code, sections) = Self::extract_code(root_path, section, sections);
-        let mut wgsl = WgslModule::new(&code, sections)?;
+        let mut wgsl = WgslModule::new(&code, sections, capabilities)?;
         let bindings = wgsl.configure_bindings();
         wgsl.configure_buffer_types();
+        let module_info = wgsl.validate_code()?;
         Ok(Self {
-            code: wgsl.to_code()?,
+            code: wgsl.to_code(&module_info),
             types: wgsl.extract_types(),
             wgsl,
+            info: module_info,
             bindings,
             section: section.clone(),
         })
@@ -153,6 +202,12 @@ impl Module {
             .filter(|(_, binding)| binding.kind == BindingKind::Storage)
     }
 
+    pub(crate) fn used_storage_bindings(&self) -> impl Iterator<Item = (&String, &Binding)> + '_ {
+        let used_names = self.wgsl.used_global_names(&self.info);
+        self.storage_bindings()
+            .filter(move |(name, _)| used_names.contains(*name))
+    }
+
     pub(crate) fn uniform_bindings(&self) -> impl Iterator<Item = (&String, &Binding)> + '_ {
         self.bindings
             .iter()
@@ -180,6 +235,22 @@ impl Module {
         self.types.get(&type_name)
     }
 
+    pub(crate) fn section(&self) -> &Section {
+        &self.section
+    }
+
+    /// Returns the items declared in the main section of the module.
+    ///
+    /// Spans are relative to the code of the main section.
+    pub(crate) fn section_items(&self) -> Vec<Item> {
+        let code_len = self.section.code().len();
+        self.wgsl
+            .items()
+            .into_iter()
+            .filter(|item| item.span.as_ref().is_none_or(|span| span.end <= code_len))
+            .collect()
+    }
+
     pub(crate) fn main_directive(&self) -> &Directive {
         &self.wgsl.sections[0].directive
     }
@@ -0,0 +1,186 @@
+use crate::directives::{Directive, DirectiveKind};
+use crate::program::section::Section;
+use crate::Program;
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// The resolved list of shader executions of a program, in execution order.
+///
+/// At each frame, all compute steps are executed first, followed by all render steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionPlan {
+    /// Steps run in a compute pass, including init steps.
+    pub compute_steps: Vec<ExecutionStep>,
+    /// Steps run in the render pass.
+    pub render_steps: Vec<ExecutionStep>,
+}
+
+impl ExecutionPlan {
+    pub(crate) fn new(program: &Program) -> Self {
+        Self {
+            compute_steps: program
+                .sections
+                .run_directives()
+                .map(|(directive, section)| ExecutionStep::new(program, directive, section))
+                .collect(),
+            render_steps: program
+                .sections
+                .draw_directives()
+                .map(|(directive, section)| ExecutionStep::new(program, directive, section))
+                .collect(),
+        }
+    }
+
+    fn fmt_steps(f: &mut Formatter<'_>, steps: &[ExecutionStep], offset: usize) -> fmt::Result {
+        if steps.is_empty() {
+            writeln!(f, "  (none)")?;
+        }
+        for (index, step) in steps.iter().enumerate() {
+            write!(f, "  {}. {step}", offset + index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ExecutionPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Compute pass:")?;
+        Self::fmt_steps(f, &self.compute_steps, 0)?;
+        writeln!(f, "Render pass:")?;
+        Self::fmt_steps(f, &self.render_steps, self.compute_steps.len())
+    }
+}
+
+/// A shader execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionStep {
+    /// The kind of execution.
+    pub kind: ExecutionKind,
+    /// The executed shader (e.g. `folder.file.shader`).
+    pub shader: String,
+    /// The module containing the `#init`, `#run` or `#draw` directive.
+    pub caller: String,
+    /// The priority of the execution.
+    pub priority: i32,
+    /// The workgroup count of the executed shader, only defined for compute shaders.
+    pub workgroup_count: Option<(u16, u16, u16)>,
+    /// The buffers bound as uniforms, as `(param, buffer)` pairs.
+    pub args: Vec<(String, String)>,
+    /// The vertex buffer, only defined for render shaders.
+    pub vertex_buffer: Option<String>,
+    /// The instance buffer, only defined for render shaders.
+    pub instance_buffer: Option<String>,
+    /// The storage buffers used by the shader.
+    pub storages: Vec<String>,
+    /// The toggle buffers enabling the execution.
+    ///
+    /// The execution is enabled if at least one of them is enabled, or if there is no toggle.
+    pub toggles: Vec<String>,
+}
+
+impl ExecutionStep {
+    fn new(program: &Program, directive: &Directive, section: &Section) -> Self {
+        let kind = ExecutionKind::new(directive);
+        let item_ident = directive.item_ident(&program.root_path);
+        let module = if kind == ExecutionKind::Draw {
+            &program.modules.render[&item_ident]
+        } else {
+            &program.modules.compute[&item_ident]
+        };
+        let is_draw = kind == ExecutionKind::Draw;
+        Self {
+            kind,
+            shader: directive.item_id(&program.root_path),
+            caller: section.id(&program.root_path),
+            priority: directive.priority(),
+            workgroup_count: (!is_draw).then(|| module.main_directive().workgroup_count()),
+            args: directive
+                .args()
+                .into_iter()
+                .map(|arg| (arg.name.slice, arg.value.path()))
+                .collect(),
+            vertex_buffer: is_draw.then(|| directive.vertex_buffer().path()),
+            instance_buffer: is_draw.then(|| directive.instance_buffer().path()),
+            storages: module
+                .used_storage_bindings()
+                .map(|(name, _)| name.clone())
+                .sorted_unstable()
+                .collect(),
+            toggles: section.toggle_var_names.clone(),
+        }
+    }
+}
+
+impl Display for ExecutionStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} (priority: {}",
+            self.kind, self.shader, self.priority
+        )?;
+        if let Some((x, y, z)) = self.workgroup_count {
+            write!(f, ", workgroups: {x}x{y}x{z}")?;
+        }
+        writeln!(f, ")")?;
+        writeln!(f, "     called from: {}", self.caller)?;
+        if !self.args.is_empty() {
+            let args = self
+                .args
+                .iter()
+                .map(|(name, buffer)| format!("{name}={buffer}"))
+                .join(", ");
+            writeln!(f, "     args: {args}")?;
+        }
+        if let Some(buffer) = &self.vertex_buffer {
+            writeln!(f, "     vertex buffer: {buffer}")?;
+        }
+        if let Some(buffer) = &self.instance_buffer {
+            writeln!(f, "     instance buffer: {buffer}")?;
+        }
+        if !self.storages.is_empty() {
+            writeln!(f, "     storages: {}", self.storages.join(", "))?;
+        }
+        if !self.toggles.is_empty() {
+            writeln!(f, "     toggles: {}", self.toggles.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The kind of shader execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionKind {
+    /// Compute shader executed once before run steps, and again each time its toggle is re-enabled
+    /// (`#init` directive).
+    Init,
+    /// Compute shader executed at each frame (`#run` directive).
+    Run,
+    /// Render shader executed at each frame (`#draw` directive).
+    Draw,
+}
+
+impl ExecutionKind {
+    pub(crate) fn new(directive: &Directive) -> Self {
+        match directive.kind() {
+            DirectiveKind::Init => Self::Init,
+            DirectiveKind::Draw => Self::Draw,
+            DirectiveKind::Run
+            | DirectiveKind::Mod
+            | DirectiveKind::ComputeShader
+            | DirectiveKind::RenderShader
+            | DirectiveKind::Import
+            | DirectiveKind::Toggle => Self::Run,
+        }
+    }
+}
+
+impl Display for ExecutionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Init => "init",
+            Self::Run => "run",
+            Self::Draw => "draw",
+        })
+    }
+}
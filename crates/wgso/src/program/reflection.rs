@@ -0,0 +1,307 @@
+use crate::directives::{Directive, DirectiveKind};
+use crate::program::module::{Module, Storage};
+use crate::program::plan::ExecutionKind;
+use crate::program::section::Section;
+use crate::program::type_::Type;
+use crate::program::wgsl::{Binding, BindingKind};
+use crate::{program, Program};
+use itertools::Itertools;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// A section of a program, delimited by a `#mod` or `#shader` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionInfo {
+    /// The unique identifier of the section (e.g. `folder.file.section`).
+    pub id: String,
+    /// The kind of section.
+    pub kind: SectionKind,
+    /// The directive defining the section.
+    pub directive: DirectiveInfo,
+    /// The span of the section in its file.
+    pub span: Range<usize>,
+    /// The identifiers of the sections imported with `#import` directives.
+    pub imports: Vec<String>,
+}
+
+impl SectionInfo {
+    pub(crate) fn new(program: &Program, section: &Section) -> Self {
+        Self {
+            id: section.id(&program.root_path),
+            kind: match section.directive.kind() {
+                DirectiveKind::ComputeShader => SectionKind::ComputeShader,
+                DirectiveKind::RenderShader => SectionKind::RenderShader,
+                DirectiveKind::Mod
+                | DirectiveKind::Init
+                | DirectiveKind::Run
+                | DirectiveKind::Draw
+                | DirectiveKind::Import
+                | DirectiveKind::Toggle => SectionKind::Mod,
+            },
+            directive: DirectiveInfo::new(&section.directive),
+            span: section.span.clone(),
+            imports: section
+                .directives()
+                .filter(|directive| directive.kind() == DirectiveKind::Import)
+                .map(|directive| directive.item_id(&program.root_path))
+                .collect(),
+        }
+    }
+}
+
+/// The kind of a program section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    /// A module defined with `#mod` directive.
+    Mod,
+    /// A compute shader defined with `#shader<compute>` directive.
+    ComputeShader,
+    /// A render shader defined with `#shader<render, ...>` directive.
+    RenderShader,
+}
+
+/// A directive written in a program file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveInfo {
+    /// The normalized code of the directive.
+    pub code: String,
+    /// The path of the file containing the directive.
+    pub path: PathBuf,
+    /// The span of the directive in its file.
+    pub span: Range<usize>,
+}
+
+impl DirectiveInfo {
+    fn new(directive: &Directive) -> Self {
+        Self {
+            code: directive.code(),
+            path: directive.path().into(),
+            span: directive.span(),
+        }
+    }
+}
+
+/// A storage buffer of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageInfo {
+    /// The name of the storage variable.
+    pub name: String,
+    /// The type of the storage variable.
+    pub type_: TypeInfo,
+    /// The identifiers of the sections declaring the storage variable.
+    pub sections: Vec<String>,
+    /// Whether the buffer is only allocated when a toggle is enabled.
+    pub is_toggleable: bool,
+}
+
+impl StorageInfo {
+    pub(crate) fn new(program: &Program, name: &str, storage: &Storage) -> Self {
+        Self {
+            name: name.into(),
+            type_: TypeInfo::new(&storage.type_),
+            sections: storage
+                .declarations
+                .iter()
+                .map(|decl| program::dotted_path(&program.root_path, &decl.raw_module_path))
+                .sorted_unstable()
+                .dedup()
+                .collect(),
+            is_toggleable: !storage.is_declared_in_non_toggleable_module,
+        }
+    }
+}
+
+/// A WGSL type with its memory layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeInfo {
+    /// The WGSL label of the type (e.g. `vec2<f32>` or `MyStruct`).
+    pub label: String,
+    /// The size of the type in bytes.
+    pub size: u32,
+    /// The offset in bytes relative to the root type.
+    pub offset: u32,
+    /// The item type and length if the type is an array.
+    pub array: Option<(Box<Self>, u32)>,
+    /// The fields if the type is a structure.
+    pub fields: Vec<FieldInfo>,
+}
+
+impl TypeInfo {
+    pub(crate) fn new(type_: &Type) -> Self {
+        Self {
+            label: type_.label.clone(),
+            size: type_.size,
+            offset: type_.offset,
+            array: type_
+                .array_params
+                .as_ref()
+                .map(|(item_type, length)| (Box::new(Self::new(item_type)), *length)),
+            fields: type_
+                .fields
+                .iter()
+                .map(|field| FieldInfo {
+                    name: field.name.clone(),
+                    type_: Self::new(&field.type_),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A field of a WGSL structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    pub type_: TypeInfo,
+}
+
+/// A compute or render shader of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderInfo {
+    /// The unique identifier of the shader (e.g. `folder.file.shader`).
+    pub id: String,
+    /// The kind of shader.
+    pub kind: ShaderKind,
+    /// The directive defining the shader.
+    pub directive: DirectiveInfo,
+    /// The workgroup count, only defined for compute shaders.
+    pub workgroup_count: Option<(u16, u16, u16)>,
+    /// The vertex type, only defined for render shaders.
+    pub vertex_type: Option<String>,
+    /// The instance type, only defined for render shaders.
+    pub instance_type: Option<String>,
+    /// The bindings of the shader, sorted by index.
+    pub bindings: Vec<BindingInfo>,
+    /// The WGSL code sent to the GPU, including imported sections.
+    pub code: String,
+}
+
+impl ShaderInfo {
+    pub(crate) fn new(program: &Program, module: &Module) -> Self {
+        let directive = module.main_directive();
+        let is_render = directive.kind() == DirectiveKind::RenderShader;
+        Self {
+            id: module.section().id(&program.root_path),
+            kind: if is_render {
+                ShaderKind::Render
+            } else {
+                ShaderKind::Compute
+            },
+            directive: DirectiveInfo::new(directive),
+            workgroup_count: (!is_render).then(|| directive.workgroup_count()),
+            vertex_type: is_render.then(|| directive.vertex_type().slice.clone()),
+            instance_type: is_render.then(|| directive.instance_type().slice.clone()),
+            bindings: module
+                .storage_bindings()
+                .chain(module.uniform_bindings())
+                .map(|(name, binding)| BindingInfo::new(name, binding))
+                .sorted_unstable_by_key(|binding| binding.index)
+                .collect(),
+            code: module.code.clone(),
+        }
+    }
+}
+
+/// The kind of a shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderKind {
+    /// A compute shader.
+    Compute,
+    /// A render shader.
+    Render,
+}
+
+/// A binding of a shader.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindingInfo {
+    /// The name of the bound variable.
+    pub name: String,
+    /// The kind of binding.
+    pub kind: BindingInfoKind,
+    /// The binding index in the bind group.
+    pub index: u32,
+    /// Whether the shader can only read the bound buffer.
+    pub is_read_only: bool,
+    /// The type of the bound variable.
+    pub type_: TypeInfo,
+}
+
+impl BindingInfo {
+    fn new(name: &str, binding: &Binding) -> Self {
+        Self {
+            name: name.into(),
+            kind: match binding.kind {
+                BindingKind::Storage => BindingInfoKind::Storage,
+                BindingKind::Uniform => BindingInfoKind::Uniform,
+            },
+            index: binding.index,
+            is_read_only: binding.is_read_only,
+            type_: TypeInfo::new(&binding.type_),
+        }
+    }
+}
+
+/// The kind of a shader binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingInfoKind {
+    /// A storage buffer shared between shaders.
+    Storage,
+    /// A uniform buffer whose value is passed as argument of `#init`, `#run` or `#draw` directive.
+    Uniform,
+}
+
+/// A toggle defined with a `#toggle` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToggleInfo {
+    /// The buffer path enabling the toggle when its value is not zero.
+    pub buffer: String,
+    /// The path prefix of toggled sections (e.g. `folder.file`).
+    pub prefix: String,
+    /// The `#toggle` directive.
+    pub directive: DirectiveInfo,
+    /// The identifiers of the toggled sections.
+    pub sections: Vec<String>,
+}
+
+impl ToggleInfo {
+    pub(crate) fn new(program: &Program, directive: &Directive) -> Self {
+        let prefix = directive.segment_path(&program.root_path);
+        Self {
+            buffer: directive.toggle_value_buffer().path(),
+            prefix: program::dotted_path(&program.root_path, &prefix),
+            directive: DirectiveInfo::new(directive),
+            sections: program
+                .sections
+                .iter()
+                .filter(|section| section.raw_path().starts_with(&prefix))
+                .map(|section| section.id(&program.root_path))
+                .collect(),
+        }
+    }
+}
+
+/// A shader call defined with an `#init`, `#run` or `#draw` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    /// The kind of call.
+    pub kind: ExecutionKind,
+    /// The identifier of the called shader.
+    pub shader: String,
+    /// The identifier of the section containing the directive.
+    pub caller: String,
+    /// The call directive.
+    pub directive: DirectiveInfo,
+}
+
+impl CallSite {
+    pub(crate) fn new(program: &Program, directive: &Directive, section: &Section) -> Self {
+        Self {
+            kind: ExecutionKind::new(directive),
+            shader: directive.item_id(&program.root_path),
+            caller: section.id(&program.root_path),
+            directive: DirectiveInfo::new(directive),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::directives::{toggle, Directive, DirectiveKind};
+use crate::program;
 use crate::program::file::{File, Files};
 use fxhash::FxHashMap;
 use itertools::Itertools;
@@ -149,6 +150,17 @@ impl Section {
         )
     }
 
+    pub(crate) fn raw_path(&self) -> PathBuf {
+        self.file
+            .path
+            .with_extension("")
+            .join(&self.directive.section_name().slice)
+    }
+
+    pub(crate) fn id(&self, root_path: &Path) -> String {
+        program::dotted_path(root_path, &self.raw_path())
+    }
+
     pub(crate) fn directives(&self) -> impl Iterator<Item = &Directive> {
         self.file
             .directives
@@ -8,7 +8,7 @@ use wgso_parser::{ParsingError, Token};
 pub(crate) struct Type {
     pub(crate) size: u32,
     pub(crate) label: String,
-    pub(crate) array_params: Option<(Box<Type>, u32)>,
+    pub(crate) array_params: Option<(Box<Self>, u32)>,
     pub(crate) offset: u32, // relative to root parent type
     pub(crate) fields: Vec<TypeField>,
 }
@@ -3,10 +3,11 @@ use crate::program::section::Section;
 use crate::program::type_;
 use crate::program::type_::Type;
 use crate::Error;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use naga::back::wgsl::{Writer, WriterFlags};
 use naga::valid::{Capabilities, ModuleInfo, ValidationFlags, Validator};
 use naga::{AddressSpace, Module, ResourceBinding, StorageAccess, TypeInner};
+use std::ops::Range;
 use std::sync::Arc;
 
 pub(crate) const BINDING_GROUP: u32 = 0;
@@ -15,14 +16,23 @@ pub(crate) const BINDING_GROUP: u32 = 0;
 pub(crate) struct WgslModule {
     module: Module,
     pub(crate) sections: Vec<Arc<Section>>,
+    capabilities: Capabilities,
 }
 
 #[allow(clippy::cast_possible_truncation)]
 impl WgslModule {
-    pub(crate) fn new(code: &str, sections: Vec<Arc<Section>>) -> Result<Self, Error> {
+    pub(crate) fn new(
+        code: &str,
+        sections: Vec<Arc<Section>>,
+        capabilities: Capabilities,
+    ) -> Result<Self, Error> {
         naga::front::wgsl::parse_str(code)
             .map_err(|error| Error::WgslParsing(sections.clone(), error))
-            .map(|module| Self { module, sections })
+            .map(|module| Self {
+                module,
+                sections,
+                capabilities,
+            })
             .and_then(Self::check_unsupported_features)
     }
 
@@ -83,13 +93,12 @@ impl WgslModule {
         max_location_count
     }
 
-    pub(crate) fn to_code(&self) -> Result<String, Error> {
-        let module_info = self.validate_code()?;
+    pub(crate) fn to_code(&self, module_info: &ModuleInfo) -> String {
         let mut code = String::new();
         Writer::new(&mut code, WriterFlags::empty())
-            .write(&self.module, &module_info)
+            .write(&self.module, module_info)
             .expect("internal error: parsed WGSL code should be valid");
-        Ok(code)
+        code
     }
 
     pub(crate) fn extract_types(&self) -> FxHashMap<String, Type> {
@@ -103,6 +112,85 @@ impl WgslModule {
             .collect()
     }
 
+    pub(crate) fn used_global_names(&self, module_info: &ModuleInfo) -> FxHashSet<String> {
+        (0..self.module.entry_points.len())
+            .flat_map(|index| {
+                let entry_point_info = module_info.get_entry_point(index);
+                self.module
+                    .global_variables
+                    .iter()
+                    .filter(move |(handle, _)| !entry_point_info[*handle].is_empty())
+                    .filter_map(|(_, var)| var.name.clone())
+            })
+            .collect()
+    }
+
+    /// Returns the named module-scope types, constants, variables and functions.
+    pub(crate) fn items(&self) -> Vec<Item> {
+        let types = self.module.types.iter().filter_map(|(handle, type_)| {
+            let (kind, fields) = if let TypeInner::Struct { members, .. } = &type_.inner {
+                let names = members.iter().filter_map(|member| member.name.clone());
+                ("struct", names.collect())
+            } else {
+                ("alias", vec![])
+            };
+            Some(Item {
+                kind,
+                name: type_.name.clone()?,
+                span: self.module.types.get_span(handle).to_range(),
+                fields,
+            })
+        });
+        let constants = self
+            .module
+            .constants
+            .iter()
+            .filter_map(|(handle, constant)| {
+                Some(Item {
+                    kind: "const",
+                    name: constant.name.clone()?,
+                    span: self.module.constants.get_span(handle).to_range(),
+                    fields: vec![],
+                })
+            });
+        let variables = self
+            .module
+            .global_variables
+            .iter()
+            .filter_map(|(handle, var)| {
+                Some(Item {
+                    kind: "var",
+                    name: var.name.clone()?,
+                    span: self.module.global_variables.get_span(handle).to_range(),
+                    fields: vec![],
+                })
+            });
+        let functions = self
+            .module
+            .functions
+            .iter()
+            .filter_map(|(handle, function)| {
+                Some(Item {
+                    kind: "fn",
+                    name: function.name.clone()?,
+                    span: self.module.functions.get_span(handle).to_range(),
+                    fields: vec![],
+                })
+            });
+        let entry_points = self.module.entry_points.iter().map(|entry_point| Item {
+            kind: "fn",
+            name: entry_point.name.clone(),
+            span: None,
+            fields: vec![],
+        });
+        types
+            .chain(constants)
+            .chain(variables)
+            .chain(functions)
+            .chain(entry_points)
+            .collect()
+    }
+
     fn check_unsupported_features(self) -> Result<Self, Error> {
         if self.module.overrides.is_empty() {
             Ok(self)
@@ -179,18 +267,25 @@ impl WgslModule {
             })
     }
 
-    fn validate_code(&self) -> Result<ModuleInfo, Error> {
-        match Validator::new(ValidationFlags::all(), Capabilities::all())
-            .subgroup_stages(naga::valid::ShaderStages::all())
-            .subgroup_operations(naga::valid::SubgroupOperationSet::all())
-            .validate(&self.module)
-        {
+    pub(crate) fn validate_code(&self) -> Result<ModuleInfo, Error> {
+        match Validator::new(ValidationFlags::all(), self.capabilities).validate(&self.module) {
             Ok(module_info) => Ok(module_info),
             Err(error) => Err(Error::WgslValidation(self.sections.clone(), error)),
         }
     }
 }
 
+/// A named module-scope item of a parsed module.
+#[derive(Debug)]
+pub(crate) struct Item {
+    pub(crate) kind: &'static str,
+    pub(crate) name: String,
+    /// The span in the module code, `None` if not kept by naga (i.e. for aliases and entry points).
+    pub(crate) span: Option<Range<usize>>,
+    /// The member names of a struct.
+    pub(crate) fields: Vec<String>,
+}
+
 #[derive(Debug)]
 pub(crate) struct Binding {
     pub(crate) kind: BindingKind,
@@ -0,0 +1,89 @@
+use wgpu::{Adapter, Backends, Instance, PowerPreference, RequestAdapterOptions, Surface};
+
+/// Options used to select the GPU adapter of a [`Runner`](crate::Runner).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdapterOptions {
+    /// The backends that can be used.
+    ///
+    /// If `None`, the backends defined by the `WGPU_BACKEND` environment variable are used,
+    /// or all backends if the variable is not set.
+    pub backends: Option<Backends>,
+    /// The index or the name of the adapter to use, as listed by [`adapters`].
+    ///
+    /// A name matches an adapter if the adapter name contains it, ignoring case.
+    /// If an adapter is specified, the power preference and the fallback options are ignored.
+    /// This option is ignored on Web.
+    pub adapter: Option<String>,
+    /// The power preference, overriding the one of the program configuration.
+    pub power_preference: Option<PowerPreference>,
+    /// Whether to force the use of the fallback adapter (e.g. a software renderer).
+    pub is_fallback_forced: bool,
+}
+
+impl AdapterOptions {
+    pub(crate) fn backends(&self) -> Backends {
+        self.backends
+            .unwrap_or_else(|| Backends::from_env().unwrap_or_else(Backends::all))
+    }
+
+    #[allow(clippy::future_not_send)]
+    pub(crate) async fn select(
+        &self,
+        instance: &Instance,
+        window_surface: Option<&Surface<'_>>,
+        power_preference: PowerPreference,
+    ) -> Result<Adapter, String> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(adapter) = &self.adapter {
+            return self.find(instance, window_surface, adapter);
+        }
+        let adapter_request = RequestAdapterOptions {
+            power_preference: self.power_preference.unwrap_or(power_preference),
+            force_fallback_adapter: self.is_fallback_forced,
+            compatible_surface: window_surface,
+        };
+        instance
+            .request_adapter(&adapter_request)
+            .await
+            .map_err(|error| format!("no supported graphic adapter found: {error}"))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn find(
+        &self,
+        instance: &Instance,
+        window_surface: Option<&Surface<'_>>,
+        adapter: &str,
+    ) -> Result<Adapter, String> {
+        let adapters = instance.enumerate_adapters(self.backends());
+        let index = adapter.parse::<usize>().ok();
+        let name = adapter.to_lowercase();
+        adapters
+            .into_iter()
+            .enumerate()
+            .find(|(adapter_index, adapter)| {
+                let info = adapter.get_info();
+                let is_matching = index.map_or_else(
+                    || info.name.to_lowercase().contains(&name),
+                    |index| index == *adapter_index,
+                );
+                is_matching
+                    && window_surface.is_none_or(|surface| adapter.is_surface_supported(surface))
+            })
+            .map(|(_, adapter)| adapter)
+            .ok_or_else(|| format!("no supported graphic adapter found matching `{adapter}`"))
+    }
+}
+
+/// Returns the information of available GPU adapters.
+///
+/// Only adapters supporting one of the backends of `options` are returned.
+/// Adapters are ordered by index, as expected by [`AdapterOptions::adapter`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn adapters(options: &AdapterOptions) -> Vec<wgpu::AdapterInfo> {
+    crate::runner::gpu::create_instance(options.backends())
+        .enumerate_adapters(options.backends())
+        .iter()
+        .map(Adapter::get_info)
+        .collect()
+}
@@ -0,0 +1,66 @@
+use crate::runner::std::read_u32;
+use fxhash::FxHashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+
+/// An event sent by the program to the host with `send_event` standard function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Event {
+    /// The event kind.
+    pub kind: u32,
+    /// The event parameters.
+    pub params: [u32; Self::PARAM_COUNT],
+}
+
+impl Event {
+    /// The number of parameters of an event.
+    pub const PARAM_COUNT: usize = 4;
+    /// Event kind requesting the application to exit.
+    pub const EXIT: u32 = 0;
+    /// Event kind requesting to toggle fullscreen mode.
+    pub const TOGGLE_FULLSCREEN: u32 = 1;
+    /// Event kind requesting to change cursor visibility.
+    ///
+    /// The first parameter is `1` to show the cursor, or `0` to hide it.
+    pub const SET_CURSOR_VISIBILITY: u32 = 2;
+    /// The first event kind available for custom events.
+    pub const CUSTOM: u32 = 1024;
+
+    pub(crate) fn decode(data: &[u8], items_offset: usize, stride: usize) -> Vec<Self> {
+        let Some(count) = data.get(..4).map(|count| read_u32(count, 0)) else {
+            return vec![];
+        };
+        data.get(items_offset..)
+            .unwrap_or_default()
+            .chunks_exact(stride)
+            .take(count as usize)
+            .map(|item| Self {
+                kind: read_u32(item, 0),
+                params: [1, 2, 3, 4].map(|index| read_u32(item, index * 4)),
+            })
+            .collect()
+    }
+}
+
+type EventHandler = Box<dyn FnMut(&Event)>;
+
+#[derive(Default)]
+pub(crate) struct EventHandlers(FxHashMap<u32, Vec<EventHandler>>);
+
+impl Debug for EventHandlers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl EventHandlers {
+    pub(crate) fn add(&mut self, kind: u32, handler: EventHandler) {
+        self.0.entry(kind).or_default().push(handler);
+    }
+
+    pub(crate) fn dispatch(&mut self, event: &Event) {
+        for handler in self.0.get_mut(&event.kind).into_iter().flatten() {
+            handler(event);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt::Debug;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+/// A source of gamepad events.
+///
+/// A backend is registered with [`Runner::set_gamepad_backend`](crate::Runner::set_gamepad_backend)
+/// to update gamepad state of `std_` storage.
+pub trait GamepadBackend: Debug {
+    /// Returns the gamepad events received since the last call.
+    fn poll_events(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// A gamepad event.
+///
+/// Gamepads are identified by a backend-specific ID.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    /// A gamepad has been connected.
+    Connected(u32),
+    /// A gamepad has been disconnected.
+    Disconnected(u32),
+    /// A gamepad button has been pressed.
+    ButtonPressed(u32, GamepadButton),
+    /// A gamepad button has been released.
+    ButtonReleased(u32, GamepadButton),
+    /// A gamepad axis value has changed.
+    ///
+    /// The value is between -1 and 1 for sticks, and between 0 and 1 for triggers.
+    AxisChanged(u32, GamepadAxis, f32),
+}
+
+/// A gamepad button.
+///
+/// Buttons are named according to their position on the gamepad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// Bottom action button.
+    South,
+    /// Right action button.
+    East,
+    /// Top action button.
+    North,
+    /// Left action button.
+    West,
+    /// Left bumper.
+    LeftBumper,
+    /// Right bumper.
+    RightBumper,
+    /// Left trigger.
+    LeftTrigger,
+    /// Right trigger.
+    RightTrigger,
+    /// Select button.
+    Select,
+    /// Start button.
+    Start,
+    /// Mode button.
+    Mode,
+    /// Left stick button.
+    LeftStick,
+    /// Right stick button.
+    RightStick,
+    /// D-pad up button.
+    DPadUp,
+    /// D-pad down button.
+    DPadDown,
+    /// D-pad left button.
+    DPadLeft,
+    /// D-pad right button.
+    DPadRight,
+}
+
+/// A gamepad axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    /// Horizontal axis of the left stick, positive to the right.
+    LeftStickX,
+    /// Vertical axis of the left stick, positive to the top.
+    LeftStickY,
+    /// Horizontal axis of the right stick, positive to the right.
+    RightStickX,
+    /// Vertical axis of the right stick, positive to the top.
+    RightStickY,
+    /// Left trigger axis, between 0 and 1.
+    LeftTrigger,
+    /// Right trigger axis, between 0 and 1.
+    RightTrigger,
+}
+
+/// A gamepad backend returning events sent with [`SyntheticGamepadBackend::send`].
+///
+/// This is mainly useful to test programs without physical gamepad.
+/// Clones of the backend share the same event queue.
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticGamepadBackend {
+    events: Arc<Mutex<Vec<GamepadEvent>>>,
+}
+
+impl GamepadBackend for SyntheticGamepadBackend {
+    fn poll_events(&mut self) -> Vec<GamepadEvent> {
+        mem::take(&mut *self.events.lock().expect("cannot lock gamepad events"))
+    }
+}
+
+impl SyntheticGamepadBackend {
+    /// Sends an event that will be returned at the next poll.
+    pub fn send(&self, event: GamepadEvent) {
+        self.events
+            .lock()
+            .expect("cannot lock gamepad events")
+            .push(event);
+    }
+}
+
+/// A gamepad backend retrieving events of physical gamepads.
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+#[derive(Debug)]
+pub struct GilrsGamepadBackend {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+impl GamepadBackend for GilrsGamepadBackend {
+    fn poll_events(&mut self) -> Vec<GamepadEvent> {
+        let mut events = vec![];
+        while let Some(event) = self.gilrs.next_event() {
+            let Ok(id) = u32::try_from(usize::from(event.id)) else {
+                continue;
+            };
+            events.extend(Self::convert_event(id, event.event));
+        }
+        events
+    }
+}
+
+#[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+impl GilrsGamepadBackend {
+    /// Creates a backend connected to the gamepads of the platform.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if gamepads are not supported on the current platform.
+    pub fn new() -> Result<Self, gilrs::Error> {
+        gilrs::Gilrs::new().map(|gilrs| Self { gilrs })
+    }
+
+    #[allow(clippy::wildcard_enum_match_arm)]
+    fn convert_event(id: u32, event: gilrs::EventType) -> Option<GamepadEvent> {
+        match event {
+            gilrs::EventType::Connected => Some(GamepadEvent::Connected(id)),
+            gilrs::EventType::Disconnected => Some(GamepadEvent::Disconnected(id)),
+            gilrs::EventType::ButtonPressed(button, _) => {
+                Self::convert_button(button).map(|button| GamepadEvent::ButtonPressed(id, button))
+            }
+            gilrs::EventType::ButtonReleased(button, _) => {
+                Self::convert_button(button).map(|button| GamepadEvent::ButtonReleased(id, button))
+            }
+            gilrs::EventType::ButtonChanged(gilrs::Button::LeftTrigger2, value, _) => Some(
+                GamepadEvent::AxisChanged(id, GamepadAxis::LeftTrigger, value),
+            ),
+            gilrs::EventType::ButtonChanged(gilrs::Button::RightTrigger2, value, _) => Some(
+                GamepadEvent::AxisChanged(id, GamepadAxis::RightTrigger, value),
+            ),
+            gilrs::EventType::AxisChanged(axis, value, _) => {
+                let axis = match axis {
+                    gilrs::Axis::LeftStickX => GamepadAxis::LeftStickX,
+                    gilrs::Axis::LeftStickY => GamepadAxis::LeftStickY,
+                    gilrs::Axis::RightStickX => GamepadAxis::RightStickX,
+                    gilrs::Axis::RightStickY => GamepadAxis::RightStickY,
+                    _ => return None,
+                };
+                Some(GamepadEvent::AxisChanged(id, axis, value))
+            }
+            _ => None,
+        }
+    }
+
+    #[allow(clippy::wildcard_enum_match_arm)]
+    const fn convert_button(button: gilrs::Button) -> Option<GamepadButton> {
+        Some(match button {
+            gilrs::Button::South => GamepadButton::South,
+            gilrs::Button::East => GamepadButton::East,
+            gilrs::Button::North => GamepadButton::North,
+            gilrs::Button::West => GamepadButton::West,
+            gilrs::Button::LeftTrigger => GamepadButton::LeftBumper,
+            gilrs::Button::RightTrigger => GamepadButton::RightBumper,
+            gilrs::Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+            gilrs::Button::RightTrigger2 => GamepadButton::RightTrigger,
+            gilrs::Button::Select => GamepadButton::Select,
+            gilrs::Button::Start => GamepadButton::Start,
+            gilrs::Button::Mode => GamepadButton::Mode,
+            gilrs::Button::LeftThumb => GamepadButton::LeftStick,
+            gilrs::Button::RightThumb => GamepadButton::RightStick,
+            gilrs::Button::DPadUp => GamepadButton::DPadUp,
+            gilrs::Button::DPadDown => GamepadButton::DPadDown,
+            gilrs::Button::DPadLeft => GamepadButton::DPadLeft,
+            gilrs::Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        })
+    }
+}
@@ -1,14 +1,15 @@
+use crate::config::ProgramConfig;
 use crate::Error;
 use std::sync::Arc;
 use wgpu::{
     Adapter, BackendOptions, Backends, BindGroupLayout, Buffer, BufferDescriptor, BufferUsages,
     Color, CommandEncoder, CommandEncoderDescriptor, CompositeAlphaMode, ComputePass,
     ComputePassDescriptor, Device, DeviceDescriptor, Extent3d, Features, Instance, InstanceFlags,
-    Limits, LoadOp, MemoryHints, Operations, PipelineLayout, PipelineLayoutDescriptor,
-    PowerPreference, Queue, RenderPass, RenderPassColorAttachment,
-    RenderPassDepthStencilAttachment, RenderPassDescriptor, RequestAdapterOptions, StoreOp,
-    Surface, SurfaceConfiguration, SurfaceTexture, Texture, TextureDescriptor, TextureDimension,
-    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor, Trace,
+    Limits, LoadOp, MemoryHints, Operations, PipelineLayout, PipelineLayoutDescriptor, PresentMode,
+    Queue, RenderPass, RenderPassColorAttachment, RenderPassDepthStencilAttachment,
+    RenderPassDescriptor, StoreOp, Surface, SurfaceConfiguration, SurfaceTexture, Texture,
+    TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+    TextureViewDescriptor, Trace,
 };
 use winit::dpi::PhysicalSize;
 use winit::event_loop::ActiveEventLoop;
@@ -35,36 +36,24 @@ pub(crate) fn padded_unpadded_row_bytes(width: u32) -> (u32, u32) {
     )
 }
 
-pub(crate) fn create_instance() -> Instance {
+pub(crate) fn create_instance(backends: Backends) -> Instance {
     Instance::new(&wgpu::InstanceDescriptor {
-        backends: Backends::from_env().unwrap_or_else(Backends::all),
+        backends,
         flags: InstanceFlags::default(),
         backend_options: BackendOptions::default(),
     })
 }
 
 #[allow(clippy::future_not_send)]
-pub(crate) async fn create_adapter(
-    instance: &Instance,
-    window_surface: Option<&Surface<'_>>,
-) -> Adapter {
-    let adapter_request = RequestAdapterOptions {
-        power_preference: PowerPreference::default(),
-        force_fallback_adapter: false,
-        compatible_surface: window_surface,
-    };
-    instance
-        .request_adapter(&adapter_request)
-        .await
-        .expect("no supported graphic adapter found")
-}
-
-#[allow(clippy::future_not_send)]
-pub(crate) async fn create_device(adapter: &Adapter) -> (Device, Queue) {
+pub(crate) async fn create_device(
+    adapter: &Adapter,
+    required_features: Features,
+    required_limits: Limits,
+) -> (Device, Queue) {
     let device_descriptor = DeviceDescriptor {
         label: Some("wgso:device"),
-        required_features: Features::default(),
-        required_limits: Limits::default(),
+        required_features,
+        required_limits,
         memory_hints: MemoryHints::Performance,
         trace: Trace::Off,
     };
@@ -140,6 +129,7 @@ pub(crate) fn create_render_pass<'a>(
     encoder: &'a mut CommandEncoder,
     view: &'a TextureView,
     depth_buffer: &'a TextureView,
+    (is_color_cleared, is_depth_cleared): (bool, bool),
 ) -> RenderPass<'a> {
     encoder.begin_render_pass(&RenderPassDescriptor {
         label: Some("wgso:render_pass"),
@@ -147,14 +137,22 @@ pub(crate) fn create_render_pass<'a>(
             view,
             resolve_target: None,
             ops: Operations {
-                load: LoadOp::Clear(Color::BLACK),
+                load: if is_color_cleared {
+                    LoadOp::Clear(Color::BLACK)
+                } else {
+                    LoadOp::Load
+                },
                 store: StoreOp::Store,
             },
         })],
         depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
             view: depth_buffer,
             depth_ops: Some(Operations {
-                load: LoadOp::Clear(1.0),
+                load: if is_depth_cleared {
+                    LoadOp::Clear(1.0)
+                } else {
+                    LoadOp::Load
+                },
                 store: StoreOp::Store,
             }),
             stencil_ops: None,
@@ -178,13 +176,14 @@ pub(crate) fn pipeline_layout(
 
 // coverage: off (window cannot be tested)
 
-pub(crate) fn create_window(event_loop: &ActiveEventLoop, size: (u32, u32)) -> Arc<Window> {
-    let size = PhysicalSize::new(size.0, size.1);
+pub(crate) fn create_window(event_loop: &ActiveEventLoop, config: &ProgramConfig) -> Arc<Window> {
+    let size = PhysicalSize::new(config.window.size.0, config.window.size.1);
     let window = event_loop
         .create_window(
             Window::default_attributes()
-                .with_title("")
-                .with_inner_size(size),
+                .with_title(config.title())
+                .with_inner_size(size)
+                .with_resizable(config.window.resizable),
         )
         .expect("cannot create window");
     Arc::new(window)
@@ -201,6 +200,7 @@ pub(crate) fn create_surface_config(
     device: &Device,
     surface: &Surface<'_>,
     size: (u32, u32),
+    is_vsync_enabled: bool,
 ) -> SurfaceConfiguration {
     let format = surface.get_capabilities(adapter).formats[0];
     let config = SurfaceConfiguration {
@@ -211,7 +211,11 @@ pub(crate) fn create_surface_config(
         format: format.remove_srgb_suffix(),
         width: size.0,
         height: size.1,
-        present_mode: surface.get_capabilities(adapter).present_modes[0],
+        present_mode: if is_vsync_enabled {
+            surface.get_capabilities(adapter).present_modes[0]
+        } else {
+            PresentMode::AutoNoVsync
+        },
         desired_maximum_frame_latency: 2,
         alpha_mode: CompositeAlphaMode::Auto,
         view_formats: vec![format.add_srgb_suffix()],
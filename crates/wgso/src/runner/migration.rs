@@ -0,0 +1,344 @@
+use crate::program::module::Storage;
+use crate::program::type_::Type;
+use fxhash::FxHashMap;
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use wgpu::COPY_BUFFER_ALIGNMENT;
+
+/// A report of storage migration run during a hot reload.
+///
+/// Data are copied field by field, matching fields by name and type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageMigration {
+    /// The paths of fields whose data have been kept (e.g. `my_buffer.field.inner`).
+    pub copied: Vec<String>,
+    /// The paths of new fields, initialized with zeros.
+    pub added: Vec<String>,
+    /// The paths of removed fields, whose data have been dropped.
+    pub removed: Vec<String>,
+    /// The paths of fields whose type has changed or whose data cannot be copied because they are
+    /// not aligned on 4 bytes (e.g. some `f16` fields), reset with zeros.
+    pub reset: Vec<String>,
+    /// The paths of arrays whose length has changed, truncated or completed with zeros.
+    pub resized: Vec<String>,
+}
+
+impl StorageMigration {
+    /// Returns the migration report and the byte ranges to copy for each changed storage.
+    ///
+    /// Storages with an unchanged type are not part of the returned ranges,
+    /// so that their buffer can be reused as is.
+    pub(crate) fn new(
+        old_storages: &FxHashMap<String, Storage>,
+        new_storages: &FxHashMap<String, Storage>,
+    ) -> (Self, FxHashMap<String, Vec<CopyRange>>) {
+        let mut migration = Self::default();
+        let mut storage_ranges = FxHashMap::default();
+        for (name, new_storage) in new_storages
+            .iter()
+            .sorted_unstable_by_key(|(name, _)| *name)
+        {
+            if let Some(old_storage) = old_storages.get(name) {
+                if !is_same_type(&old_storage.type_, &new_storage.type_) {
+                    let mut ranges = vec![];
+                    collect_copy_ranges(
+                        &old_storage.type_,
+                        &new_storage.type_,
+                        (0, 0),
+                        name,
+                        &mut ranges,
+                        &mut Some(&mut migration),
+                    );
+                    storage_ranges.insert(name.clone(), align_ranges(ranges, &mut migration));
+                }
+            } else {
+                migration.added.push(name.clone());
+            }
+        }
+        migration.removed.extend(
+            old_storages
+                .keys()
+                .filter(|name| !new_storages.contains_key(*name))
+                .sorted_unstable()
+                .cloned(),
+        );
+        (migration, storage_ranges)
+    }
+
+    /// Returns whether no storage has been changed.
+    pub fn is_empty(&self) -> bool {
+        self.copied.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.reset.is_empty()
+            && self.resized.is_empty()
+    }
+}
+
+impl Display for StorageMigration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Storages migrated:")?;
+        for (label, paths) in [
+            ("copied", &self.copied),
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("reset", &self.reset),
+            ("resized", &self.resized),
+        ] {
+            if !paths.is_empty() {
+                writeln!(f, "  {label}: {}", paths.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A range of bytes to copy from an old storage buffer to a new one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyRange {
+    pub(crate) old_offset: u64,
+    pub(crate) new_offset: u64,
+    pub(crate) size: u64,
+    fields: Vec<(String, u64)>, // path and size of copied fields, in offset order
+}
+
+impl CopyRange {
+    // Buffer copies require aligned offsets and sizes, which is not always the case
+    // (e.g. for fields of type `f16`), so the range is truncated to its longest aligned part.
+    fn align(mut self) -> (Option<Self>, Vec<String>) {
+        let is_start_aligned = [self.old_offset, self.new_offset]
+            .iter()
+            .all(|offset| offset % COPY_BUFFER_ALIGNMENT == 0);
+        let mut aligned_field_count = 0;
+        let mut aligned_size = 0;
+        if is_start_aligned {
+            let mut size = 0;
+            for (index, (_, field_size)) in self.fields.iter().enumerate() {
+                size += field_size;
+                if size % COPY_BUFFER_ALIGNMENT == 0 {
+                    aligned_field_count = index + 1;
+                    aligned_size = size;
+                }
+            }
+        }
+        let dropped_paths = self
+            .fields
+            .drain(aligned_field_count..)
+            .map(|(path, _)| path)
+            .collect();
+        self.size = aligned_size;
+        ((self.size > 0).then_some(self), dropped_paths)
+    }
+}
+
+fn align_ranges(ranges: Vec<CopyRange>, migration: &mut StorageMigration) -> Vec<CopyRange> {
+    let mut aligned_ranges = vec![];
+    for range in ranges {
+        let (range, dropped_paths) = range.align();
+        aligned_ranges.extend(range);
+        for path in dropped_paths {
+            migration.copied.retain(|copied_path| copied_path != &path);
+            if !migration.reset.contains(&path) {
+                migration.reset.push(path);
+            }
+        }
+    }
+    aligned_ranges
+}
+
+fn is_same_type(old_type: &Type, new_type: &Type) -> bool {
+    old_type == new_type
+        && old_type.size == new_type.size
+        && old_type
+            .fields
+            .iter()
+            .zip(&new_type.fields)
+            .all(|(old_field, new_field)| {
+                old_field.type_.offset - old_type.offset == new_field.type_.offset - new_type.offset
+                    && is_same_type(&old_field.type_, &new_field.type_)
+            })
+        && old_type
+            .array_params
+            .as_ref()
+            .zip(new_type.array_params.as_ref())
+            .is_none_or(|((old_item, _), (new_item, _))| is_same_type(old_item, new_item))
+}
+
+fn collect_copy_ranges(
+    old_type: &Type,
+    new_type: &Type,
+    (old_offset, new_offset): (u64, u64),
+    path: &str,
+    ranges: &mut Vec<CopyRange>,
+    report: &mut Option<&mut StorageMigration>,
+) {
+    if is_same_type(old_type, new_type) {
+        push_range(ranges, old_offset, new_offset, new_type.size.into(), path);
+        if let Some(report) = report {
+            report.copied.push(path.into());
+        }
+    } else if !old_type.fields.is_empty() && !new_type.fields.is_empty() {
+        for new_field in &new_type.fields {
+            let field_path = format!("{path}.{}", new_field.name);
+            if let Some(old_field) = old_type
+                .fields
+                .iter()
+                .find(|old_field| old_field.name == new_field.name)
+            {
+                let offsets = (
+                    old_offset + u64::from(old_field.type_.offset - old_type.offset),
+                    new_offset + u64::from(new_field.type_.offset - new_type.offset),
+                );
+                collect_copy_ranges(
+                    &old_field.type_,
+                    &new_field.type_,
+                    offsets,
+                    &field_path,
+                    ranges,
+                    report,
+                );
+            } else if let Some(report) = report {
+                report.added.push(field_path);
+            }
+        }
+        if let Some(report) = report {
+            report.removed.extend(
+                old_type
+                    .fields
+                    .iter()
+                    .filter(|old_field| {
+                        !new_type
+                            .fields
+                            .iter()
+                            .any(|new_field| new_field.name == old_field.name)
+                    })
+                    .map(|old_field| format!("{path}.{}", old_field.name)),
+            );
+        }
+    } else if let (Some((old_item, old_len)), Some((new_item, new_len))) =
+        (&old_type.array_params, &new_type.array_params)
+    {
+        if let Some(report) = report {
+            if old_len != new_len {
+                report.resized.push(path.into());
+            }
+        }
+        let old_stride = u64::from(old_type.size / old_len.max(&1));
+        let new_stride = u64::from(new_type.size / new_len.max(&1));
+        for index in 0..u64::from(*old_len.min(new_len)) {
+            let offsets = (
+                old_offset + index * old_stride,
+                new_offset + index * new_stride,
+            );
+            // items share the same layout, so only the first one is reported
+            let mut item_report = if index == 0 {
+                report.as_deref_mut()
+            } else {
+                None
+            };
+            collect_copy_ranges(old_item, new_item, offsets, path, ranges, &mut item_report);
+        }
+    } else if let Some(report) = report {
+        report.reset.push(path.into());
+    }
+}
+
+fn push_range(
+    ranges: &mut Vec<CopyRange>,
+    old_offset: u64,
+    new_offset: u64,
+    size: u64,
+    path: &str,
+) {
+    if let Some(last) = ranges.last_mut() {
+        if last.old_offset + last.size == old_offset && last.new_offset + last.size == new_offset {
+            last.size += size;
+            last.fields.push((path.into(), size));
+            return;
+        }
+    }
+    ranges.push(CopyRange {
+        old_offset,
+        new_offset,
+        size,
+        fields: vec![(path.into(), size)],
+    });
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::program::module::Storage;
+    use crate::program::type_::Type;
+    use crate::runner::migration::{CopyRange, StorageMigration};
+    use fxhash::FxHashMap;
+    use naga::front::wgsl;
+    use std::iter;
+    use std::sync::Arc;
+
+    #[test]
+    fn migrate_unaligned_fields() {
+        let old_storages = storages("a: f16, b: f16, c: f16, d: u32");
+        let new_storages = storages("a: f16, b: f16, c: f16, added: f16, d: u32");
+        let (migration, ranges) = StorageMigration::new(&old_storages, &new_storages);
+        assert_eq!(migration.copied, ["state.a", "state.b", "state.d"]);
+        assert_eq!(migration.reset, ["state.c"]);
+        assert_eq!(migration.added, ["state.added"]);
+        assert_eq!(
+            ranges["state"],
+            [
+                CopyRange {
+                    old_offset: 0,
+                    new_offset: 0,
+                    size: 4,
+                    fields: vec![("state.a".into(), 2), ("state.b".into(), 2)],
+                },
+                CopyRange {
+                    old_offset: 8,
+                    new_offset: 8,
+                    size: 4,
+                    fields: vec![("state.d".into(), 4)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn migrate_moved_fields() {
+        let old_storages = storages("@size(8) a: u32, b: u32");
+        let new_storages = storages("a: u32, @size(8) b: u32");
+        let (migration, ranges) = StorageMigration::new(&old_storages, &new_storages);
+        assert_eq!(migration.copied, ["state.a", "state.b"]);
+        assert_eq!(
+            ranges["state"],
+            [
+                CopyRange {
+                    old_offset: 0,
+                    new_offset: 0,
+                    size: 4,
+                    fields: vec![("state.a".into(), 4)],
+                },
+                CopyRange {
+                    old_offset: 8,
+                    new_offset: 4,
+                    size: 4,
+                    fields: vec![("state.b".into(), 4)],
+                },
+            ]
+        );
+    }
+
+    fn storages(fields: &str) -> FxHashMap<String, Storage> {
+        let code = format!("enable f16; struct State {{ {fields} }} var<storage> state: State;");
+        let module = wgsl::parse_str(&code).unwrap();
+        let var = module.global_variables.iter().next().unwrap().1;
+        let type_ = Type::new(&module, &module.types[var.ty], 0);
+        let storage = Storage {
+            type_: Arc::new(type_),
+            declarations: vec![],
+            is_declared_in_non_toggleable_module: true,
+        };
+        iter::once(("state".into(), storage)).collect()
+    }
+}
@@ -1,27 +1,40 @@
+use crate::config::ProgramConfig;
 use crate::directives::DirectiveKind;
 use crate::program::file::SourceFolder;
 use crate::program::module::Storage;
+use crate::runner::adapter::AdapterOptions;
+use crate::runner::event::{Event, EventHandlers};
+use crate::runner::gamepad::GamepadBackend;
+use crate::runner::migration::StorageMigration;
+use crate::runner::overlay::TextOverlay;
+use crate::runner::provider::StateProvider;
 use crate::runner::shaders::RenderShaderResources;
-use crate::runner::std::StdState;
+use crate::runner::std::{StdReadback, StdState, StdWindowState};
 use crate::runner::target::{Target, TargetConfig, TargetSpecialized, TextureTarget, WindowTarget};
 use crate::{Error, Program};
 use ::std::mem;
 use ::std::path::PathBuf;
 use ::std::sync::Arc;
 use futures::executor;
-use fxhash::FxHashMap;
+use fxhash::{FxHashMap, FxHashSet};
 use shader_execution::ShaderExecution;
 use shaders::ComputeShaderResources;
 use watcher::RunnerWatcher;
 use wgpu::{
-    Adapter, Buffer, BufferDescriptor, BufferUsages, ComputePass, Device, ErrorFilter, Extent3d,
-    Instance, MapMode, PollType, Queue, RenderPass, TexelCopyBufferInfo, TexelCopyBufferLayout,
-    TextureFormat, TextureViewDescriptor,
+    Buffer, BufferDescriptor, BufferUsages, CommandEncoder, ComputePass, Device, ErrorFilter,
+    Extent3d, Features, Limits, MapMode, PollType, Queue, RenderPass, TexelCopyBufferInfo,
+    TexelCopyBufferLayout, TextureFormat, TextureView, TextureViewDescriptor,
 };
 use winit::dpi::PhysicalSize;
-use winit::window::Window;
+use winit::window::{CursorGrabMode, Fullscreen, Window};
 
+pub(crate) mod adapter;
+pub(crate) mod event;
+pub(crate) mod gamepad;
 pub(crate) mod gpu;
+pub(crate) mod migration;
+mod overlay;
+pub(crate) mod provider;
 mod shader_execution;
 mod shaders;
 mod std;
@@ -33,9 +46,7 @@ mod watcher;
 pub struct Runner {
     pub(crate) std_state: StdState,
     target: Target,
-    instance: Instance,
     device: Device,
-    adapter: Adapter,
     queue: Queue,
     program: Program,
     compute_shaders: FxHashMap<(PathBuf, String), ComputeShaderResources>,
@@ -46,6 +57,12 @@ pub struct Runner {
     is_initialized: bool,
     is_toggle_enabled: FxHashMap<String, bool>,
     watcher: RunnerWatcher,
+    pending_changed_paths: FxHashSet<PathBuf>,
+    error_overlay: Option<TextOverlay>,
+    state_providers: Vec<Box<dyn StateProvider>>,
+    event_handlers: EventHandlers,
+    gamepad_backend: Option<Box<dyn GamepadBackend>>,
+    std_readback: Option<StdReadback>,
 }
 
 impl Runner {
@@ -73,29 +90,80 @@ impl Runner {
         window: Option<Arc<Window>>,
         size: Option<(u32, u32)>,
     ) -> Result<Self, Program> {
-        let folder_path = source.path();
-        let program = Program::parse(source);
-        if !program.errors.is_empty() {
-            return Err(program.with_sorted_errors());
-        }
+        Self::with_adapter_async(source, window, size, &AdapterOptions::default()).await
+    }
+
+    /// Creates a new runner from a WGSO program directory, using a GPU adapter
+    /// selected with `adapter_options`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the program initialization has failed.
+    pub fn with_adapter(
+        source: impl SourceFolder + Send,
+        window: Option<Arc<Window>>,
+        size: Option<(u32, u32)>,
+        adapter_options: &AdapterOptions,
+    ) -> Result<Self, Program> {
+        executor::block_on(Self::with_adapter_async(
+            source,
+            window,
+            size,
+            adapter_options,
+        ))
+    }
+
+    /// Creates a new runner from a WGSO program directory, using a GPU adapter
+    /// selected with `adapter_options`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the program initialization has failed.
+    #[allow(clippy::future_not_send)]
+    pub async fn with_adapter_async(
+        source: impl SourceFolder + Send,
+        window: Option<Arc<Window>>,
+        size: Option<(u32, u32)>,
+        adapter_options: &AdapterOptions,
+    ) -> Result<Self, Program> {
+        let mut program = Self::parse(source)?;
         let target = TargetConfig {
-            size: size.unwrap_or((800, 600)),
+            size: size.unwrap_or(program.config.window.size),
         };
-        let instance = gpu::create_instance();
+        let instance = gpu::create_instance(adapter_options.backends());
         let window_surface = window.map(|window| {
             // coverage: off (window cannot be tested)
             let surface = gpu::create_surface(&instance, window.clone());
             (window, surface)
         }); // coverage: on
-        let adapter = gpu::create_adapter(
-            &instance,
-            window_surface.as_ref().map(|(_, surface)| surface),
-        )
-        .await;
-        let (device, queue) = gpu::create_device(&adapter).await;
+        let adapter = match adapter_options
+            .select(
+                &instance,
+                window_surface.as_ref().map(|(_, surface)| surface),
+                program.config.power_preference.into(),
+            )
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(error) => {
+                program.errors.push(Error::AdapterNotFound(error));
+                return Err(program);
+            }
+        };
+        let requirements =
+            Self::device_requirements(&program, adapter.features(), &adapter.limits(), "adapter");
+        let (features, limits) = match requirements {
+            Ok(requirements) => requirements,
+            Err(error) => {
+                program.errors.push(error);
+                return Err(program);
+            }
+        };
+        let (device, queue) = gpu::create_device(&adapter, features, limits).await;
         let surface_config = window_surface.as_ref().map(|(_, surface)| {
             // coverage: off (window cannot be tested)
-            gpu::create_surface_config(&adapter, &device, surface, target.size)
+            let is_vsync_enabled = program.config.vsync;
+            gpu::create_surface_config(&adapter, &device, surface, target.size, is_vsync_enabled)
         }); // coverage: on
         let depth_buffer = gpu::create_depth_buffer(&device, target.size);
         let target = if let (Some((window, surface)), Some(surface_config)) =
@@ -107,6 +175,8 @@ impl Runner {
                     window,
                     surface,
                     surface_config,
+                    instance,
+                    adapter,
                 }),
                 config: target,
                 depth_buffer,
@@ -121,14 +191,126 @@ impl Runner {
                 depth_buffer,
             }
         };
+        Self::with_target(program, target, device, queue).await
+    }
+
+    /// Creates a new runner from a WGSO program directory, using an existing WGPU device.
+    ///
+    /// The program is rendered in a target of `size` with format `texture_format`,
+    /// which is provided at each step with [`Runner::run_step_into`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the program initialization has failed, or if the device doesn't
+    /// support the GPU features and limits required by the program configuration.
+    pub fn with_device(
+        source: impl SourceFolder + Send,
+        device: &Device,
+        queue: &Queue,
+        texture_format: TextureFormat,
+        size: (u32, u32),
+    ) -> Result<Self, Program> {
+        executor::block_on(Self::with_device_async(
+            source,
+            device,
+            queue,
+            texture_format,
+            size,
+        ))
+    }
+
+    /// Creates a new runner from a WGSO program directory, using an existing WGPU device.
+    ///
+    /// The program is rendered in a target of `size` with format `texture_format`,
+    /// which is provided at each step with [`Runner::run_step_into`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the program initialization has failed, or if the device doesn't
+    /// support the GPU features and limits required by the program configuration.
+    #[allow(clippy::future_not_send)]
+    pub async fn with_device_async(
+        source: impl SourceFolder + Send,
+        device: &Device,
+        queue: &Queue,
+        texture_format: TextureFormat,
+        size: (u32, u32),
+    ) -> Result<Self, Program> {
+        let mut program = Self::parse(source)?;
+        let requirements =
+            Self::device_requirements(&program, device.features(), &device.limits(), "device");
+        if let Err(error) = requirements {
+            program.errors.push(error);
+            return Err(program);
+        }
+        let target = Target {
+            inner: TargetSpecialized::External(texture_format),
+            config: TargetConfig { size },
+            depth_buffer: gpu::create_depth_buffer(device, size),
+        };
+        Self::with_target(program, target, device.clone(), queue.clone()).await
+    }
+
+    fn parse(source: impl SourceFolder) -> Result<Program, Program> {
+        let program = Program::parse(source);
+        if program.errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(program.with_sorted_errors())
+        }
+    }
+
+    fn device_requirements(
+        program: &Program,
+        supported_features: Features,
+        supported_limits: &Limits,
+        gpu_name: &str,
+    ) -> Result<(Features, Limits), Error> {
+        let config = &program.config;
+        let features = config
+            .features()
+            .expect("internal error: features should be validated");
+        let limits = config
+            .limits()
+            .expect("internal error: limits should be validated");
+        let missing_features = features - supported_features;
+        let error = if missing_features.is_empty() {
+            let mut unsupported_limits = vec![];
+            limits.check_limits_with_fail_fn(supported_limits, false, |name, _, _| {
+                unsupported_limits.push(name);
+            });
+            if unsupported_limits.is_empty() {
+                return Ok((features, limits));
+            }
+            format!(
+                "GPU limits not supported by the {gpu_name}: {}",
+                unsupported_limits.join(", ")
+            )
+        } else {
+            format!("GPU features not supported by the {gpu_name}: {missing_features}")
+        };
+        let path = ProgramConfig::path(&program.root_path);
+        Err(Error::InvalidConfig(path, error))
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn with_target(
+        program: Program,
+        target: Target,
+        device: Device,
+        queue: Queue,
+    ) -> Result<Self, Program> {
+        let folder_path = program.root_path.clone();
         let mut runner = Self {
             std_state: StdState::default(),
             is_toggle_enabled: Self::toggle_var_names(&program)
-                .map(|var_name| (var_name, false))
+                .map(|var_name| {
+                    let is_enabled = program.config.enabled_toggles.contains(&var_name);
+                    (var_name, is_enabled)
+                })
                 .collect(),
             target,
             device,
-            adapter,
             queue,
             compute_shaders: FxHashMap::default(),
             render_shaders: FxHashMap::default(),
@@ -137,8 +319,13 @@ impl Runner {
             buffers: Self::create_buffers(&program),
             program,
             is_initialized: false,
-            instance,
             watcher: RunnerWatcher::new(&folder_path),
+            pending_changed_paths: FxHashSet::default(),
+            error_overlay: None,
+            state_providers: vec![],
+            event_handlers: EventHandlers::default(),
+            gamepad_backend: None,
+            std_readback: None,
         };
         if runner.load_shaders(None).await {
             Ok(runner)
@@ -152,6 +339,11 @@ impl Runner {
         self.std_state.time.frame_delta_secs
     }
 
+    /// Returns the running program.
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
     /// Lists all GPU buffer names.
     pub fn buffers(&self) -> impl Iterator<Item = &str> {
         self.program.modules.storages.keys().map(String::as_str)
@@ -181,42 +373,12 @@ impl Runner {
     /// If the buffer doesn't exist, an empty vector is returned.
     /// Inner fields can also be provided (e.g. `my_buffer.field.inner`).
     pub fn read(&self, path: &str) -> Vec<u8> {
-        let Some(field) = self.program.parse_field(path) else {
-            return vec![];
-        };
-        let Some(buffer) = &self.buffers[&field.buffer_name] else {
-            return vec![];
-        };
-        let read_buffer = self.device.create_buffer(&BufferDescriptor {
-            label: Some("wgso:storage_read_buffer"),
-            size: field.type_.size.into(),
-            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
-            mapped_at_creation: false,
-        });
-        let mut encoder = gpu::create_encoder(&self.device);
-        encoder.copy_buffer_to_buffer(
-            buffer,
-            field.type_.offset.into(),
-            &read_buffer,
-            0,
-            field.type_.size.into(),
-        );
-        let submission_index = self.queue.submit(Some(encoder.finish()));
-        let slice = read_buffer.slice(..);
-        slice.map_async(MapMode::Read, |_| ());
-        self.device
-            .poll(PollType::WaitForSubmissionIndex(submission_index))
-            .expect("cannot read buffer");
-        let view = slice.get_mapped_range();
-        let content = view.to_vec();
-        drop(view);
-        read_buffer.unmap();
-        content
+        self.read_fields(&[path]).pop().unwrap_or_default()
     }
 
     /// Read texture target.
     ///
-    /// If the surface is not a texture, an empty vector is returned.
+    /// If the surface is not a texture owned by the runner, an empty vector is returned.
     pub fn read_target(&self) -> Vec<u8> {
         match &self.target.inner {
             TargetSpecialized::Texture(target) => {
@@ -262,7 +424,7 @@ impl Runner {
                 tmp_buffer.unmap();
                 content
             }
-            TargetSpecialized::Window(_) => vec![], // no-coverage (window cannot be tested)
+            TargetSpecialized::Window(_) | TargetSpecialized::External(_) => vec![],
         }
     }
 
@@ -270,43 +432,303 @@ impl Runner {
     ///
     /// # Errors
     ///
-    /// An error is returned if shader execution failed.
+    /// An error is returned if shader execution failed, or if the runner has been created with
+    /// [`Runner::with_device`] (in this case, [`Runner::run_step_into`] should be used instead).
     pub fn run_step(&mut self) -> Result<(), &Program> {
-        self.device.push_error_scope(ErrorFilter::Validation);
-        self.apply_toggle();
-        if !self.is_initialized {
-            self.std_state.update(self.target.config.size);
+        // errors of the previous step are not reported again
+        self.program.errors.clear();
+        self.std_readback = None;
+        if matches!(self.target.inner, TargetSpecialized::External(_)) {
+            self.program.errors.push(Error::UnsupportedOperation(
+                "runner with external device should be run with `run_step_into`".into(),
+            ));
+            return Err(&self.program);
         }
-        self.write_std_state();
+        self.device.push_error_scope(ErrorFilter::Validation);
         let mut encoder = gpu::create_encoder(&self.device);
-        let pass = gpu::start_compute_pass(&mut encoder);
-        self.run_compute_step(pass);
+        let are_providers_valid = self.run_compute_pass(&mut encoder);
         let mut render_shader_executions = mem::take(&mut self.render_shader_executions);
         match &self.target.inner {
             // coverage: off (window cannot be tested)
             TargetSpecialized::Window(target) => {
                 let texture = target.create_surface_texture();
                 let view = gpu::create_surface_view(&texture, target.surface_config.format);
-                let pass = gpu::create_render_pass(&mut encoder, &view, &self.target.depth_buffer);
+                let pass = gpu::create_render_pass(
+                    &mut encoder,
+                    &view,
+                    &self.target.depth_buffer,
+                    (true, true),
+                );
                 self.run_draw_step(pass, &mut render_shader_executions);
                 self.queue.submit(Some(encoder.finish()));
                 texture.present();
             }
             // coverage: on
             TargetSpecialized::Texture(target) => {
-                let pass =
-                    gpu::create_render_pass(&mut encoder, &target.view, &self.target.depth_buffer);
+                let pass = gpu::create_render_pass(
+                    &mut encoder,
+                    &target.view,
+                    &self.target.depth_buffer,
+                    (true, true),
+                );
                 self.run_draw_step(pass, &mut render_shader_executions);
                 self.queue.submit(Some(encoder.finish()));
             }
+            TargetSpecialized::External(_) => {
+                unreachable!("internal error: external target should be checked")
+            }
         }
         self.render_shader_executions = render_shader_executions;
+        self.finish_step(are_providers_valid)
+    }
+
+    /// Runs a step of the program by recording GPU commands in an existing encoder.
+    ///
+    /// The program is drawn over the existing content of `view`, which must have the texture
+    /// format passed to [`Runner::with_device`].
+    /// If provided, `depth_view` must have the `Depth32Float` format.
+    /// Otherwise, a depth buffer managed by the runner is used and cleared at each step.
+    ///
+    /// The caller is responsible for submitting the encoder to the queue.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if shader execution failed.
+    pub fn run_step_into(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        view: &TextureView,
+        depth_view: Option<&TextureView>,
+    ) -> Result<(), &Program> {
+        self.program.errors.clear();
+        self.std_readback = None;
+        self.device.push_error_scope(ErrorFilter::Validation);
+        let are_providers_valid = self.run_compute_pass(encoder);
+        let mut render_shader_executions = mem::take(&mut self.render_shader_executions);
+        // the caller depth buffer is kept to draw above the existing scene
+        let pass = if let Some(depth_view) = depth_view {
+            gpu::create_render_pass(encoder, view, depth_view, (false, false))
+        } else {
+            gpu::create_render_pass(encoder, view, &self.target.depth_buffer, (false, true))
+        };
+        self.run_draw_step(pass, &mut render_shader_executions);
+        self.render_shader_executions = render_shader_executions;
+        self.finish_step(are_providers_valid)
+    }
+
+    /// Reloads the runner if a file in the program directory has been updated.
+    ///
+    /// If storage structure has changed, buffers are migrated: data of fields with the same name
+    /// and type are kept, new fields are initialized with zeros and removed fields are dropped.
+    /// In this case, the migration report is returned.
+    ///
+    /// Changes of the program configuration are also applied: newly enabled toggles are enabled
+    /// and vsync is updated. Window and adapter properties are only applied at startup.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the program cannot be reloaded, including when the new
+    /// configuration requires GPU features or limits not supported by the current device
+    /// (in this case, the runner should be recreated).
+    pub fn reload_on_change(&mut self) -> Result<Option<StorageMigration>, Program> {
+        let Some(changed_paths) = self.watcher.detect_changes() else {
+            return Ok(None);
+        };
+        self.pending_changed_paths.extend(changed_paths);
+        let changed_paths = mem::take(&mut self.pending_changed_paths);
+        let result = self.reload(&changed_paths);
+        if result.is_err() {
+            // paths are kept until the next successful reload to not reuse outdated cached files
+            self.pending_changed_paths = changed_paths;
+        }
+        if let Some(overlay) = &mut self.error_overlay {
+            let errors = result.as_ref().err().map(Program::render_errors);
+            overlay.set_text(errors.as_deref());
+        }
+        result
+    }
+
+    /// Registers a provider of data written in a storage before each step.
+    ///
+    /// Providers are run in registration order, after update of `std_` storage.
+    /// If the provider storage doesn't exist or the provided data size doesn't match the storage
+    /// size, an [`Error::InvalidStateProvider`] error is returned by each step.
+    pub fn add_state_provider(&mut self, provider: impl StateProvider + 'static) {
+        self.state_providers.push(Box::new(provider));
+    }
+
+    /// Sets the backend used to retrieve gamepad events.
+    ///
+    /// Gamepad events are polled before each step to update `std_.gamepads`.
+    pub fn set_gamepad_backend(&mut self, backend: impl GamepadBackend + 'static) {
+        self.gamepad_backend = Some(Box::new(backend));
+    }
+
+    /// Registers a callback run for each event of a given kind sent by the program.
+    ///
+    /// Callbacks are run by [`Runner::drain_events`].
+    pub fn add_event_handler(&mut self, kind: u32, handler: impl FnMut(&Event) + 'static) {
+        self.event_handlers.add(kind, Box::new(handler));
+    }
+
+    /// Retrieves the events sent by the program since the last call and runs registered handlers.
+    ///
+    /// This method should be called after each step, once the step commands have been submitted.
+    /// Events are sent by the program with `send_event` function of the standard library.
+    /// If the program doesn't use the standard library, no event is returned.
+    pub fn drain_events(&mut self) -> Vec<Event> {
+        let Some((items_offset, stride)) = self.event_layout() else {
+            return vec![];
+        };
+        let data = self.std_readback().events.take().unwrap_or_default();
+        if data.is_empty() {
+            return vec![];
+        }
+        self.write("std_.events.count", &0_u32.to_ne_bytes());
+        let events = Event::decode(&data, items_offset, stride);
+        for event in &events {
+            self.event_handlers.dispatch(event);
+        }
+        events
+    }
+
+    /// Enables display of reloading errors above the rendered frame.
+    ///
+    /// Errors returned by [`Runner::reload_on_change`] are then displayed
+    /// until the next successful reload.
+    pub fn enable_error_overlay(&mut self) {
+        self.error_overlay = Some(TextOverlay::new(&self.device, self.target.texture_format()));
+    }
+
+    fn reload(
+        &mut self,
+        changed_paths: &FxHashSet<PathBuf>,
+    ) -> Result<Option<StorageMigration>, Program> {
+        let mut program = self.program.reload(changed_paths);
+        if !program.errors.is_empty() {
+            return Err(program.with_sorted_errors());
+        }
+        // the device is created at startup, so it may not support new requirements
+        let requirements = Self::device_requirements(
+            &program,
+            self.device.features(),
+            &self.device.limits(),
+            "device",
+        );
+        if let Err(error) = requirements {
+            program.errors.push(error);
+            return Err(program);
+        }
+        let migration = (program.modules.storages != self.program.modules.storages)
+            .then(|| self.migrate_buffers(&program));
+        if executor::block_on(self.load_shaders(Some(&mut program))) {
+            self.is_toggle_enabled = Self::toggle_var_names(&program)
+                .map(|var_name| {
+                    let is_enabled = self.is_toggle_enabled.get(&var_name) == Some(&true);
+                    (var_name, is_enabled)
+                })
+                .collect();
+            let previous_config = mem::replace(&mut self.program, program).config;
+            self.std_readback = None;
+            self.apply_config_changes(&previous_config);
+            Ok(migration.map(|(migration, _)| migration))
+        } else {
+            if let Some((_, old_buffers)) = migration {
+                self.buffers = old_buffers;
+            }
+            Err(program.with_sorted_errors())
+        }
+    }
+
+    fn apply_config_changes(&mut self, previous_config: &ProgramConfig) {
+        let config = &self.program.config;
+        for var_name in &config.enabled_toggles {
+            if !previous_config.enabled_toggles.contains(var_name) {
+                self.write(var_name, &1_u32.to_ne_bytes());
+            }
+        }
+        if config.vsync != previous_config.vsync
+            && matches!(self.target.inner, TargetSpecialized::Window(_))
+        {
+            // coverage: off (window cannot be tested)
+            let size = self.target.config.size;
+            self.update_surface_size(PhysicalSize::new(size.0, size.1));
+        } // coverage: on
+    }
+
+    fn migrate_buffers(
+        &mut self,
+        program: &Program,
+    ) -> (StorageMigration, FxHashMap<String, Option<Buffer>>) {
+        let (migration, storage_ranges) =
+            StorageMigration::new(&self.program.modules.storages, &program.modules.storages);
+        let mut encoder = gpu::create_encoder(&self.device);
+        let buffers = program
+            .modules
+            .storages
+            .iter()
+            .map(|(name, storage)| {
+                let old_buffer = self.buffers.get(name).cloned().flatten();
+                let buffer = match (old_buffer, storage_ranges.get(name)) {
+                    (Some(old_buffer), Some(ranges)) => {
+                        let buffer = gpu::create_buffer(
+                            &self.device,
+                            &format!("`var<storage, _> {name}`"),
+                            storage.type_.size.into(),
+                        );
+                        for range in ranges {
+                            encoder.copy_buffer_to_buffer(
+                                &old_buffer,
+                                range.old_offset,
+                                &buffer,
+                                range.new_offset,
+                                range.size,
+                            );
+                        }
+                        Some(buffer)
+                    }
+                    (old_buffer, None) => old_buffer,
+                    (None, Some(_)) => None,
+                };
+                (name.clone(), buffer)
+            })
+            .collect();
+        self.queue.submit(Some(encoder.finish()));
+        let old_buffers = mem::replace(&mut self.buffers, buffers);
+        (migration, old_buffers)
+    }
+
+    fn run_compute_pass(&mut self, encoder: &mut CommandEncoder) -> bool {
+        self.apply_toggle();
+        if !self.is_initialized {
+            self.std_state.update(self.target.config.size);
+            self.write_enabled_toggles();
+        }
+        if let Some(backend) = &mut self.gamepad_backend {
+            for event in backend.poll_events() {
+                self.std_state.gamepads.update_pad(event);
+            }
+        }
+        self.write_std_state();
+        let are_providers_valid = self.update_state_providers();
+        if let Some(overlay) = &mut self.error_overlay {
+            overlay.prepare(&self.device, self.target.config.size);
+        }
+        let pass = gpu::start_compute_pass(encoder);
+        self.run_compute_step(pass);
+        are_providers_valid
+    }
+
+    fn finish_step(&mut self, are_providers_valid: bool) -> Result<(), &Program> {
         self.std_state.update(self.target.config.size);
         #[cfg(not(target_arch = "wasm32"))]
         if let Some(error) = executor::block_on(self.device.pop_error_scope()) {
             self.program.errors.push(gpu::convert_error(error));
             return Err(&self.program);
         }
+        if !are_providers_valid {
+            return Err(&self.program);
+        }
         #[cfg(target_arch = "wasm32")]
         {
             let error = self.device.pop_error_scope();
@@ -319,31 +741,6 @@ impl Runner {
         Ok(())
     }
 
-    /// Reloads the runner if a file in the program directory has been updated.
-    ///
-    /// # Errors
-    ///
-    /// An error is returned if the program cannot be reloaded.
-    pub fn reload_on_change(&mut self) -> Result<(), Program> {
-        if !self.watcher.detect_changes() {
-            return Ok(());
-        }
-        let mut program = Program::parse(self.program.root_path.as_path());
-        if !program.errors.is_empty() {
-            return Err(program.with_sorted_errors());
-        }
-        if program.modules.storages != self.program.modules.storages {
-            program.errors.push(Error::ChangedStorageStructure);
-            return Err(program.with_sorted_errors());
-        }
-        if executor::block_on(self.load_shaders(Some(&mut program))) {
-            self.program = program;
-            Ok(())
-        } else {
-            Err(program.with_sorted_errors())
-        }
-    }
-
     fn toggle_var_names(program: &Program) -> impl Iterator<Item = String> + '_ {
         program
             .files
@@ -353,21 +750,165 @@ impl Runner {
             .map(|directive| directive.toggle_value_buffer().path())
     }
 
+    fn write_enabled_toggles(&self) {
+        for var_name in &self.program.config.enabled_toggles {
+            self.write(var_name, &1_u32.to_ne_bytes());
+        }
+    }
+
     fn write_std_state(&self) {
         self.write("std_.time", &self.std_state.time.data());
         self.write("std_.surface", &self.std_state.surface.data());
+        self.write("std_.lifecycle", &self.std_state.lifecycle.data());
         self.write("std_.keyboard", &self.std_state.keyboard.data());
         self.write("std_.mouse", &self.std_state.mouse.data());
         self.write("std_.touch", &self.std_state.touch.data());
+        self.write("std_.gamepads", &self.std_state.gamepads.data());
+    }
+
+    /// Reads fields of storage buffers with a single GPU readback.
+    ///
+    /// An empty vector is returned for each field that doesn't exist.
+    fn read_fields(&self, paths: &[&str]) -> Vec<Vec<u8>> {
+        let fields: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                let field = self.program.parse_field(path)?;
+                let buffer = self.buffers[&field.buffer_name].as_ref()?;
+                Some((buffer, field.type_.offset, field.type_.size))
+            })
+            .collect();
+        let total_size = fields
+            .iter()
+            .flatten()
+            .map(|(_, _, size)| u64::from(*size))
+            .sum();
+        if total_size == 0 {
+            return vec![vec![]; paths.len()];
+        }
+        let read_buffer = self.device.create_buffer(&BufferDescriptor {
+            label: Some("wgso:storage_read_buffer"),
+            size: total_size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = gpu::create_encoder(&self.device);
+        let mut read_offset = 0;
+        for &(buffer, offset, size) in fields.iter().flatten() {
+            encoder.copy_buffer_to_buffer(
+                buffer,
+                offset.into(),
+                &read_buffer,
+                read_offset,
+                size.into(),
+            );
+            read_offset += u64::from(size);
+        }
+        let submission_index = self.queue.submit(Some(encoder.finish()));
+        let slice = read_buffer.slice(..);
+        slice.map_async(MapMode::Read, |_| ());
+        self.device
+            .poll(PollType::WaitForSubmissionIndex(submission_index))
+            .expect("cannot read buffer");
+        let view = slice.get_mapped_range();
+        let mut read_offset = 0;
+        let contents = fields
+            .iter()
+            .map(|field| {
+                field.map_or_else(Vec::new, |(_, _, size)| {
+                    let start = read_offset;
+                    read_offset += size as usize;
+                    view[start..read_offset].to_vec()
+                })
+            })
+            .collect();
+        drop(view);
+        read_buffer.unmap();
+        contents
+    }
+
+    /// Returns `std_.events` and `std_.window` data written by the last step.
+    ///
+    /// Both fields are read with a single GPU readback, and `std_.window` is only read for
+    /// window targets.
+    fn std_readback(&mut self) -> &mut StdReadback {
+        if self.std_readback.is_none() {
+            let paths: &[&str] = if matches!(self.target.inner, TargetSpecialized::Window(_)) {
+                &["std_.events", "std_.window"]
+            } else {
+                &["std_.events"]
+            };
+            let mut data = self.read_fields(paths).into_iter();
+            self.std_readback = Some(StdReadback {
+                events: data.next(),
+                window: data.next(),
+            });
+        }
+        self.std_readback.get_or_insert_default()
+    }
+
+    fn event_layout(&self) -> Option<(usize, usize)> {
+        let events = self.program.parse_field("std_.events")?;
+        let items = self.program.parse_field("std_.events.items")?;
+        let (_, item_count) = items.type_.array_params.as_ref()?;
+        let items_offset = items.type_.offset - events.type_.offset;
+        #[allow(clippy::integer_division)]
+        let stride = items.type_.size / item_count.max(&1);
+        Some((items_offset as usize, stride as usize))
+    }
+
+    fn update_state_providers(&mut self) -> bool {
+        let mut providers = mem::take(&mut self.state_providers);
+        let mut are_valid = true;
+        for provider in &mut providers {
+            let path = provider.path().to_string();
+            let Some(size) = self
+                .program
+                .parse_field(&path)
+                .map(|field| field.type_.size as usize)
+            else {
+                self.program
+                    .errors
+                    .push(Error::InvalidStateProvider(format!(
+                        "storage `{path}` of state provider not found"
+                    )));
+                are_valid = false;
+                continue;
+            };
+            if provider.is_read_back() {
+                provider.read_back(&self.read(&path));
+            }
+            let Some(data) = provider.data() else {
+                continue;
+            };
+            if data.len() == size {
+                self.write(&path, &data);
+            } else {
+                self.program
+                    .errors
+                    .push(Error::InvalidStateProvider(format!(
+                        "state provider has returned {} bytes for storage `{path}` of {size} bytes",
+                        data.len()
+                    )));
+                are_valid = false;
+            }
+        }
+        self.state_providers = providers;
+        are_valid
     }
 
     #[allow(clippy::future_not_send)]
     async fn load_shaders(&mut self, program: Option<&mut Program>) -> bool {
         let program = program.unwrap_or(&mut self.program);
         self.device.push_error_scope(ErrorFilter::Validation);
-        let compute_shaders = Self::create_compute_shaders(&self.device, program);
-        let render_shaders =
-            Self::create_render_shaders(&self.device, program, self.target.texture_format());
+        let compute_shaders =
+            Self::create_compute_shaders(&self.device, program, &self.compute_shaders);
+        let render_shaders = Self::create_render_shaders(
+            &self.device,
+            program,
+            self.target.texture_format(),
+            &self.render_shaders,
+        );
         let compute_shader_executions = Self::create_compute_shader_runs(
             &self.device,
             program,
@@ -400,13 +941,18 @@ impl Runner {
     fn create_compute_shaders(
         device: &Device,
         program: &Program,
+        previous_shaders: &FxHashMap<(PathBuf, String), ComputeShaderResources>,
     ) -> FxHashMap<(PathBuf, String), ComputeShaderResources> {
         program
             .modules
             .compute
             .iter()
             .map(|(name, module)| {
-                let shader = ComputeShaderResources::new(module, device);
+                let shader = previous_shaders
+                    .get(name)
+                    .filter(|shader| Arc::ptr_eq(&shader.module, module))
+                    .cloned()
+                    .unwrap_or_else(|| ComputeShaderResources::new(module, device));
                 (name.clone(), shader)
             })
             .collect()
@@ -416,13 +962,18 @@ impl Runner {
         device: &Device,
         program: &Program,
         texture_format: TextureFormat,
+        previous_shaders: &FxHashMap<(PathBuf, String), RenderShaderResources>,
     ) -> FxHashMap<(PathBuf, String), RenderShaderResources> {
         program
             .modules
             .render
             .iter()
             .map(|(name, module)| {
-                let shader = RenderShaderResources::new(module, texture_format, device);
+                let shader = previous_shaders
+                    .get(name)
+                    .filter(|shader| Arc::ptr_eq(&shader.module, module))
+                    .cloned()
+                    .unwrap_or_else(|| RenderShaderResources::new(module, texture_format, device));
                 (name.clone(), shader)
             })
             .collect()
@@ -537,6 +1088,9 @@ impl Runner {
             let instance_count = self.bind_buffer(&mut pass, draw, 1, false);
             pass.draw(0..vertex_count, 0..instance_count);
         }
+        if let Some(overlay) = &self.error_overlay {
+            overlay.draw(&mut pass);
+        }
     }
 
     #[allow(clippy::cast_lossless)]
@@ -630,44 +1184,130 @@ impl Runner {
     pub fn request_redraw(&self) {
         match &self.target.inner {
             TargetSpecialized::Window(target) => target.window.request_redraw(),
-            TargetSpecialized::Texture(_) => {
+            TargetSpecialized::Texture(_) | TargetSpecialized::External(_) => {
                 unreachable!("surface should be a window")
             }
         }
     }
 
+    /// Applies the window properties written by the program in `std_.window`.
+    ///
+    /// Properties are applied only when they have changed since the last call.
+    /// They are read back from the GPU together with the events returned by
+    /// [`Runner::drain_events`], so calling both methods after a step costs a single readback.
+    /// If the target is not a window or the program doesn't use the standard library,
+    /// nothing happens.
+    pub fn update_window(&mut self) {
+        if !matches!(self.target.inner, TargetSpecialized::Window(_)) {
+            return;
+        }
+        let Some(window_field) = self.program.parse_field("std_.window") else {
+            return;
+        };
+        let window_offset = window_field.type_.offset;
+        let data = self.std_readback().window.take().unwrap_or_default();
+        if data.is_empty() {
+            return;
+        }
+        let state = StdWindowState::new(&data, |name| {
+            let field = self.program.parse_field(&format!("std_.window.{name}"))?;
+            let offset = field.type_.offset - window_offset;
+            Some((offset as usize, field.type_.size as usize))
+        });
+        let previous_state = mem::replace(&mut self.std_state.window, state.clone());
+        let TargetSpecialized::Window(target) = &self.target.inner else {
+            return;
+        };
+        let window = &target.window;
+        if state.title != previous_state.title {
+            window.set_title(&state.title);
+        }
+        if state.size != previous_state.size && state.size.0 > 0 && state.size.1 > 0 {
+            let _ = window.request_inner_size(PhysicalSize::new(state.size.0, state.size.1));
+        }
+        if state.is_fullscreen() != previous_state.is_fullscreen() {
+            window.set_fullscreen(
+                state
+                    .is_fullscreen()
+                    .then_some(Fullscreen::Borderless(None)),
+            );
+        }
+        if state.is_cursor_hidden != previous_state.is_cursor_hidden {
+            window.set_cursor_visible(!state.is_cursor_hidden);
+        }
+        if state.is_cursor_grabbed != previous_state.is_cursor_grabbed {
+            let result = if state.is_cursor_grabbed {
+                window
+                    .set_cursor_grab(CursorGrabMode::Locked)
+                    .or_else(|_| window.set_cursor_grab(CursorGrabMode::Confined))
+            } else {
+                window.set_cursor_grab(CursorGrabMode::None)
+            };
+            if let Err(error) = result {
+                log::warn!("cannot change cursor grab mode: {error}");
+            }
+        }
+        if state.is_vsync_disabled != previous_state.is_vsync_disabled {
+            let size = self.target.config.size;
+            self.update_surface_size(PhysicalSize::new(size.0, size.1));
+        }
+    }
+
+    fn is_vsync_enabled(&self) -> bool {
+        self.program.config.vsync && !self.std_state.window.is_vsync_disabled
+    }
+
+    pub(crate) fn window(&self) -> Option<&Window> {
+        match &self.target.inner {
+            TargetSpecialized::Window(target) => Some(&target.window),
+            TargetSpecialized::Texture(_) | TargetSpecialized::External(_) => None,
+        }
+    }
+
     /// Refreshes the rendering surface.
     pub fn refresh_surface(&mut self) {
+        let is_vsync_enabled = self.is_vsync_enabled();
         match &mut self.target.inner {
             TargetSpecialized::Window(target) => {
-                target.surface = gpu::create_surface(&self.instance, target.window.clone());
+                target.surface = gpu::create_surface(&target.instance, target.window.clone());
                 target.surface_config = gpu::create_surface_config(
-                    &self.adapter,
+                    &target.adapter,
                     &self.device,
                     &target.surface,
                     self.target.config.size,
+                    is_vsync_enabled,
                 );
             }
-            TargetSpecialized::Texture(_) => {
+            TargetSpecialized::Texture(_) | TargetSpecialized::External(_) => {
                 unreachable!("internal error: refreshing non-window target surface")
             }
         }
     }
 
     /// Resizes rendering surface.
+    ///
+    /// For a runner created with [`Runner::with_device`], the views passed to
+    /// [`Runner::run_step_into`] are expected to have the new size.
     pub fn update_surface_size(&mut self, size: PhysicalSize<u32>) {
+        let is_vsync_enabled = self.is_vsync_enabled();
         match &mut self.target.inner {
             TargetSpecialized::Window(target) => {
                 self.target.config.size = (size.width.max(1), size.height.max(1));
                 self.target.depth_buffer =
                     gpu::create_depth_buffer(&self.device, self.target.config.size);
                 target.surface_config = gpu::create_surface_config(
-                    &self.adapter,
+                    &target.adapter,
                     &self.device,
                     &target.surface,
                     self.target.config.size,
+                    is_vsync_enabled,
                 );
             }
+            TargetSpecialized::External(_) => {
+                self.target.config.size = (size.width.max(1), size.height.max(1));
+                self.target.depth_buffer =
+                    gpu::create_depth_buffer(&self.device, self.target.config.size);
+            }
             TargetSpecialized::Texture(_) => {
                 unreachable!("internal error: updating non-window target surface")
             }
@@ -0,0 +1,302 @@
+use crate::runner::gpu;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, CompareFunction,
+    DepthBiasState, DepthStencilState, Device, MultisampleState, PipelineCompilationOptions,
+    PrimitiveState, RenderPass, RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderStages, StencilState, TextureFormat, VertexState,
+};
+
+// 5x8 pixels per glyph, each byte is a row starting from the top (least significant byte)
+const FONT: [u64; 95] = [
+    0x00_00_00_00_00_00_00_00, // space
+    0x00_04_00_04_04_04_04_04, // !
+    0x00_00_00_00_00_0a_0a_0a, // "
+    0x00_0a_0a_1f_0a_1f_0a_0a, // #
+    0x00_04_0f_14_0e_05_1e_04, // $
+    0x00_18_19_02_04_08_13_03, // %
+    0x00_16_09_15_02_05_09_06, // &
+    0x00_00_00_00_00_02_04_04, // '
+    0x00_08_04_02_02_02_04_08, // (
+    0x00_02_04_08_08_08_04_02, // )
+    0x00_00_04_15_0e_15_04_00, // *
+    0x00_00_04_04_1f_04_04_00, // +
+    0x02_04_06_00_00_00_00_00, // ,
+    0x00_00_00_00_1f_00_00_00, // -
+    0x00_06_06_00_00_00_00_00, // .
+    0x00_00_01_02_04_08_10_00, // /
+    0x00_0e_11_13_15_19_11_0e, // 0
+    0x00_0e_04_04_04_04_06_04, // 1
+    0x00_1f_02_04_08_10_11_0e, // 2
+    0x00_0e_11_10_08_04_08_1f, // 3
+    0x00_08_08_1f_09_0a_0c_08, // 4
+    0x00_0e_11_10_10_0f_01_1f, // 5
+    0x00_0e_11_11_0f_01_02_0c, // 6
+    0x00_02_02_02_04_08_10_1f, // 7
+    0x00_0e_11_11_0e_11_11_0e, // 8
+    0x00_06_08_10_1e_11_11_0e, // 9
+    0x00_00_06_06_00_06_06_00, // :
+    0x00_02_04_06_00_06_06_00, // ;
+    0x00_08_04_02_01_02_04_08, // <
+    0x00_00_00_1f_00_1f_00_00, // =
+    0x00_02_04_08_10_08_04_02, // >
+    0x00_04_00_04_08_10_11_0e, // ?
+    0x00_0e_15_15_16_10_11_0e, // @
+    0x00_11_11_11_1f_11_11_0e, // A
+    0x00_0f_11_11_0f_11_11_0f, // B
+    0x00_0e_11_01_01_01_11_0e, // C
+    0x00_07_09_11_11_11_09_07, // D
+    0x00_1f_01_01_0f_01_01_1f, // E
+    0x00_01_01_01_0f_01_01_1f, // F
+    0x00_1e_11_11_1d_01_11_0e, // G
+    0x00_11_11_11_1f_11_11_11, // H
+    0x00_0e_04_04_04_04_04_0e, // I
+    0x00_06_09_08_08_08_08_1c, // J
+    0x00_11_09_05_03_05_09_11, // K
+    0x00_1f_01_01_01_01_01_01, // L
+    0x00_11_11_11_15_15_1b_11, // M
+    0x00_11_11_19_15_13_11_11, // N
+    0x00_0e_11_11_11_11_11_0e, // O
+    0x00_01_01_01_0f_11_11_0f, // P
+    0x00_16_09_15_11_11_11_0e, // Q
+    0x00_11_09_05_0f_11_11_0f, // R
+    0x00_0f_10_10_0e_01_01_1e, // S
+    0x00_04_04_04_04_04_04_1f, // T
+    0x00_0e_11_11_11_11_11_11, // U
+    0x00_04_0a_11_11_11_11_11, // V
+    0x00_0a_15_15_15_11_11_11, // W
+    0x00_11_11_0a_04_0a_11_11, // X
+    0x00_04_04_04_04_0a_11_11, // Y
+    0x00_1f_01_02_04_08_10_1f, // Z
+    0x00_0e_02_02_02_02_02_0e, // [
+    0x00_00_10_08_04_02_01_00, // \
+    0x00_0e_08_08_08_08_08_0e, // ]
+    0x00_00_00_00_00_11_0a_04, // ^
+    0x00_1f_00_00_00_00_00_00, // _
+    0x00_00_00_00_00_08_04_02, // `
+    0x00_1e_11_1e_10_0e_00_00, // a
+    0x00_0f_11_11_13_0d_01_01, // b
+    0x00_0e_11_01_01_0e_00_00, // c
+    0x00_1e_11_11_19_16_10_10, // d
+    0x00_0e_01_1f_11_0e_00_00, // e
+    0x00_02_02_02_07_02_12_0c, // f
+    0x0e_10_1e_11_11_1e_00_00, // g
+    0x00_11_11_11_13_0d_01_01, // h
+    0x00_0e_04_04_04_06_00_04, // i
+    0x06_09_08_08_08_0c_00_08, // j
+    0x00_09_05_03_05_09_01_01, // k
+    0x00_0e_04_04_04_04_04_06, // l
+    0x00_11_11_15_15_0b_00_00, // m
+    0x00_11_11_11_13_0d_00_00, // n
+    0x00_0e_11_11_11_0e_00_00, // o
+    0x01_01_0f_11_11_0f_00_00, // p
+    0x10_10_1e_11_11_1e_00_00, // q
+    0x00_01_01_01_13_0d_00_00, // r
+    0x00_0f_10_0e_01_1e_00_00, // s
+    0x00_0c_12_02_02_07_02_02, // t
+    0x00_16_19_11_11_11_00_00, // u
+    0x00_04_0a_11_11_11_00_00, // v
+    0x00_0a_15_15_11_11_00_00, // w
+    0x00_11_0a_04_0a_11_00_00, // x
+    0x0e_10_1e_11_11_11_00_00, // y
+    0x00_1f_02_04_08_1f_00_00, // z
+    0x00_08_04_04_02_04_04_08, // {
+    0x00_04_04_04_04_04_04_04, // |
+    0x00_02_04_04_08_04_04_02, // }
+    0x00_00_00_08_15_02_00_00, // ~
+];
+const FIRST_CHAR: char = ' ';
+const LAST_CHAR: char = '~';
+const CELL_SIZE: (u32, u32) = (12, 20);
+
+/// An overlay displaying text above the rendered frame.
+#[derive(Debug)]
+pub(crate) struct TextOverlay {
+    pipeline: RenderPipeline,
+    layout: BindGroupLayout,
+    font_buffer: Buffer,
+    text: Option<String>,
+    bind_group: Option<(BindGroup, (u32, u32))>,
+}
+
+impl TextOverlay {
+    pub(crate) fn new(device: &Device, texture_format: TextureFormat) -> Self {
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("wgso:overlay_bind_group_layout"),
+            entries: &(0..3)
+                .map(|binding| BindGroupLayoutEntry {
+                    binding,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                })
+                .collect::<Vec<_>>(),
+        });
+        Self {
+            pipeline: Self::create_pipeline(device, texture_format, &layout),
+            font_buffer: device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("wgso:overlay_font_buffer"),
+                contents: &FONT
+                    .iter()
+                    .flat_map(|glyph| glyph.to_le_bytes())
+                    .collect::<Vec<_>>(),
+                usage: BufferUsages::STORAGE,
+            }),
+            layout,
+            text: None,
+            bind_group: None,
+        }
+    }
+
+    /// Sets the displayed text, or hides the overlay if `None`.
+    ///
+    /// ANSI escape codes are removed from the text.
+    pub(crate) fn set_text(&mut self, text: Option<&str>) {
+        self.text = text.map(strip_ansi_escapes::strip_str);
+        self.bind_group = None;
+    }
+
+    #[allow(clippy::integer_division)]
+    pub(crate) fn prepare(&mut self, device: &Device, surface_size: (u32, u32)) {
+        let Some(text) = &self.text else {
+            return;
+        };
+        if self
+            .bind_group
+            .as_ref()
+            .is_some_and(|(_, size)| *size == surface_size)
+        {
+            return;
+        }
+        let column_count = (surface_size.0 / CELL_SIZE.0).max(1);
+        let max_row_count = (surface_size.1 / CELL_SIZE.1).max(1);
+        let rows: Vec<_> = wrap_lines(text, column_count as usize)
+            .into_iter()
+            .take(max_row_count as usize)
+            .collect();
+        let chars: Vec<_> = rows
+            .iter()
+            .flat_map(|row| {
+                row.chars()
+                    .map(|character| {
+                        if (FIRST_CHAR..=LAST_CHAR).contains(&character) {
+                            u32::from(character)
+                        } else {
+                            0
+                        }
+                    })
+                    .chain(std::iter::repeat(0))
+                    .take(column_count as usize)
+            })
+            .chain(std::iter::once(0))
+            .flat_map(u32::to_le_bytes)
+            .collect();
+        #[allow(clippy::cast_possible_truncation)]
+        let grid_size = [column_count, rows.len() as u32];
+        let grid_size_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("wgso:overlay_grid_size_buffer"),
+            contents: &grid_size
+                .into_iter()
+                .flat_map(u32::to_le_bytes)
+                .collect::<Vec<_>>(),
+            usage: BufferUsages::STORAGE,
+        });
+        let chars_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("wgso:overlay_chars_buffer"),
+            contents: &chars,
+            usage: BufferUsages::STORAGE,
+        });
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("wgso:overlay_bind_group"),
+            layout: &self.layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: grid_size_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: self.font_buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: chars_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        self.bind_group = Some((bind_group, surface_size));
+    }
+
+    pub(crate) fn draw(&self, pass: &mut RenderPass<'_>) {
+        if let (Some(_), Some((bind_group, _))) = (&self.text, &self.bind_group) {
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..3, 0..1);
+        }
+    }
+
+    fn create_pipeline(
+        device: &Device,
+        texture_format: TextureFormat,
+        layout: &BindGroupLayout,
+    ) -> RenderPipeline {
+        let label = "wgso:overlay";
+        let module = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some(label),
+            source: wgpu::ShaderSource::Wgsl(include_str!("overlay.wgsl").into()),
+        });
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&gpu::pipeline_layout(device, Some(layout), label)),
+            vertex: VertexState {
+                module: &module,
+                entry_point: None,
+                compilation_options: PipelineCompilationOptions::default(),
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &module,
+                entry_point: None,
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: texture_format,
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::Always,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+}
+
+fn wrap_lines(text: &str, column_count: usize) -> Vec<String> {
+    text.replace('\t', "    ")
+        .lines()
+        .flat_map(|line| {
+            let chars: Vec<_> = line.chars().collect();
+            if chars.is_empty() {
+                vec![String::new()]
+            } else {
+                chars
+                    .chunks(column_count)
+                    .map(|chunk| chunk.iter().collect())
+                    .collect()
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,37 @@
+const GLYPH_SIZE = vec2u(6, 10);
+const GLYPH_SCALE = 2u;
+const FIRST_CHAR = 32u;
+const LAST_CHAR = 126u;
+const BACKGROUND_COLOR = vec4f(0, 0, 0, 0.85);
+const TEXT_COLOR = vec4f(1, 0.6, 0.6, 1);
+
+@group(0) @binding(0)
+var<storage, read> grid_size: vec2u;
+@group(0) @binding(1)
+var<storage, read> font: array<vec2u, 95>;
+@group(0) @binding(2)
+var<storage, read> chars: array<u32>;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4f {
+    let position = vec2f(f32((index << 1) & 2), f32(index & 2));
+    return vec4f(position * 2 - 1, 0, 1);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4f) -> @location(0) vec4f {
+    let pixel = vec2u(position.xy) / GLYPH_SCALE;
+    let cell = pixel / GLYPH_SIZE;
+    if cell.x >= grid_size.x || cell.y >= grid_size.y {
+        return BACKGROUND_COLOR;
+    }
+    let code = chars[cell.y * grid_size.x + cell.x];
+    let glyph_pixel = pixel % GLYPH_SIZE;
+    if code < FIRST_CHAR || code > LAST_CHAR || glyph_pixel.x >= 5 || glyph_pixel.y >= 8 {
+        return BACKGROUND_COLOR;
+    }
+    let bit_index = glyph_pixel.y * 8 + glyph_pixel.x;
+    let glyph = font[code - FIRST_CHAR];
+    let is_set = ((glyph[bit_index / 32] >> (bit_index % 32)) & 1) == 1;
+    return select(BACKGROUND_COLOR, TEXT_COLOR, is_set);
+}
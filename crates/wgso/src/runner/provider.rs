@@ -0,0 +1,35 @@
+use std::fmt::Debug;
+
+/// A provider of CPU-side data written in a program storage before each step.
+///
+/// It can be used to feed the program with data not handled by the standard library
+/// (e.g. network or audio data), in the same way as `std_` storage is updated.
+///
+/// Providers are registered with [`Runner::add_state_provider`](crate::Runner::add_state_provider).
+pub trait StateProvider: Debug {
+    /// Returns the path of the written storage.
+    ///
+    /// Inner fields can also be provided (e.g. `my_buffer.field.inner`).
+    fn path(&self) -> &str;
+
+    /// Returns the data to write in the storage before running a step.
+    ///
+    /// The data length must match the size of the storage type.
+    /// If `None` is returned, the storage is not updated.
+    fn data(&mut self) -> Option<Vec<u8>>;
+
+    /// Returns whether storage data should be read back with [`StateProvider::read_back`].
+    ///
+    /// As reading GPU data is a blocking operation, it is disabled by default.
+    fn is_read_back(&self) -> bool {
+        false
+    }
+
+    /// Receives storage data resulting from the previous step.
+    ///
+    /// It is called before [`StateProvider::data`] if [`StateProvider::is_read_back`]
+    /// returns `true`.
+    fn read_back(&mut self, data: &[u8]) {
+        let _ = data;
+    }
+}
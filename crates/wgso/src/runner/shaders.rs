@@ -1,6 +1,7 @@
 use crate::directives::Directive;
 use crate::program::module::Module;
 use crate::runner::gpu;
+use std::sync::Arc;
 use wgpu::{
     BindGroupLayout, BindGroupLayoutEntry, BindingType, BufferBindingType, CompareFunction,
     ComputePipeline, ComputePipelineDescriptor, DepthBiasState, DepthStencilState, Device,
@@ -10,15 +11,16 @@ use wgpu::{
     VertexState, VertexStepMode,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct ComputeShaderResources {
     pub(crate) pipeline: ComputePipeline,
     pub(crate) layout: Option<BindGroupLayout>,
     pub(crate) directive: Directive,
+    pub(crate) module: Arc<Module>,
 }
 
 impl ComputeShaderResources {
-    pub(crate) fn new(module: &Module, device: &Device) -> Self {
+    pub(crate) fn new(module: &Arc<Module>, device: &Device) -> Self {
         let directive = module.main_directive();
         let layout = (module.binding_count() > 0)
             .then(|| create_bind_group_layout(directive, module, device, ShaderStages::COMPUTE));
@@ -27,6 +29,7 @@ impl ComputeShaderResources {
             pipeline,
             layout,
             directive: directive.clone(),
+            module: module.clone(),
         }
     }
 
@@ -52,21 +55,30 @@ impl ComputeShaderResources {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct RenderShaderResources {
     pub(crate) pipeline: RenderPipeline,
     pub(crate) layout: Option<BindGroupLayout>,
+    pub(crate) module: Arc<Module>,
 }
 
 impl RenderShaderResources {
-    pub(crate) fn new(module: &Module, texture_format: TextureFormat, device: &Device) -> Self {
+    pub(crate) fn new(
+        module: &Arc<Module>,
+        texture_format: TextureFormat,
+        device: &Device,
+    ) -> Self {
         let directive = module.main_directive();
         let layout = (module.binding_count() > 0).then(|| {
             create_bind_group_layout(directive, module, device, ShaderStages::VERTEX_FRAGMENT)
         });
         let pipeline =
             Self::create_pipeline(module, directive, texture_format, device, layout.as_ref());
-        Self { pipeline, layout }
+        Self {
+            pipeline,
+            layout,
+            module: module.clone(),
+        }
     }
 
     fn create_pipeline(
@@ -130,7 +142,7 @@ impl RenderShaderResources {
                 module: &module,
                 entry_point: None,
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: texture_format.add_srgb_suffix(),
+                    format: texture_format,
                     blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
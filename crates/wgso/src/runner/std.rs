@@ -1,23 +1,30 @@
+use crate::runner::gamepad::{GamepadAxis, GamepadEvent};
+use std::iter;
 use web_time::{Instant, SystemTime};
 use winit::dpi::PhysicalPosition;
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, Touch, TouchPhase};
-use winit::keyboard::KeyCode;
+use winit::event::{ElementState, KeyEvent, MouseButton, MouseScrollDelta, Touch, TouchPhase};
+use winit::keyboard::{Key, KeyCode, PhysicalKey};
 
 #[derive(Debug, Default)]
 pub(crate) struct StdState {
+    pub(crate) window: StdWindowState,
     pub(crate) time: StdTimeState,
     pub(crate) surface: SurfaceState,
+    pub(crate) lifecycle: StdLifecycleState,
     pub(crate) keyboard: StdKeyboardState,
     pub(crate) mouse: StdMouseState,
     pub(crate) touch: StdTouchState,
+    pub(crate) gamepads: StdGamepadsState,
 }
 
 impl StdState {
     pub(crate) fn update(&mut self, surface_size: (u32, u32)) {
         self.surface.update(surface_size);
+        self.lifecycle.update();
         self.keyboard.update();
         self.mouse.update();
         self.touch.update();
+        self.gamepads.update();
         self.time.update();
     }
 }
@@ -82,43 +89,367 @@ impl SurfaceState {
     }
 }
 
-// coverage: off (not easy to test)
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct StdLifecycleState {
+    pub(crate) is_focused: bool,
+    pub(crate) is_occluded: bool,
+    pub(crate) scale_factor: f32,
+    pub(crate) is_just_resized: bool,
+    pub(crate) is_just_resumed: bool,
+}
+
+impl Default for StdLifecycleState {
+    fn default() -> Self {
+        Self {
+            is_focused: true,
+            is_occluded: false,
+            scale_factor: 1.,
+            is_just_resized: false,
+            is_just_resumed: false,
+        }
+    }
+}
+
+impl StdLifecycleState {
+    pub(crate) fn data(&self) -> Vec<u8> {
+        u32::from(self.is_focused)
+            .to_ne_bytes()
+            .into_iter()
+            .chain(u32::from(self.is_occluded).to_ne_bytes())
+            .chain(self.scale_factor.to_ne_bytes())
+            .chain(u32::from(self.is_just_resized).to_ne_bytes())
+            .chain(u32::from(self.is_just_resumed).to_ne_bytes())
+            .collect()
+    }
+
+    fn update(&mut self) {
+        self.is_just_resized = false;
+        self.is_just_resumed = false;
+    }
+}
+
+/// Data of `std_` fields read back from the GPU after a step.
+///
+/// Each field is taken when it has been processed.
+#[derive(Debug, Default)]
+pub(crate) struct StdReadback {
+    pub(crate) events: Option<Vec<u8>>,
+    pub(crate) window: Option<Vec<u8>>,
+}
+
+/// Window properties written by the program in `std_.window`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct StdWindowState {
+    pub(crate) title: String,
+    pub(crate) size: (u32, u32),
+    pub(crate) mode: u32,
+    pub(crate) is_cursor_hidden: bool,
+    pub(crate) is_cursor_grabbed: bool,
+    pub(crate) is_vsync_disabled: bool,
+}
+
+impl StdWindowState {
+    pub(crate) const FULLSCREEN_MODE: u32 = 1;
+
+    /// Decodes the window properties.
+    ///
+    /// `field` returns the offset and size in `data` of a field of `std_.window`.
+    pub(crate) fn new(data: &[u8], field: impl Fn(&str) -> Option<(usize, usize)>) -> Self {
+        let value = |name: &str, byte_index: usize| {
+            field(name).map_or(0, |(offset, size)| {
+                if byte_index < size {
+                    read_u32(data, offset + byte_index)
+                } else {
+                    0
+                }
+            })
+        };
+        let title_size = field("title").map_or(0, |(_, size)| size);
+        Self {
+            title: (0..title_size)
+                .step_by(4)
+                .map(|byte_index| value("title", byte_index))
+                .take_while(|&code| code != 0)
+                .filter_map(char::from_u32)
+                .collect(),
+            size: (value("size", 0), value("size", 4)),
+            mode: value("mode", 0),
+            is_cursor_hidden: value("is_cursor_hidden", 0) != 0,
+            is_cursor_grabbed: value("is_cursor_grabbed", 0) != 0,
+            is_vsync_disabled: value("is_vsync_disabled", 0) != 0,
+        }
+    }
+
+    pub(crate) fn is_fullscreen(&self) -> bool {
+        self.mode == Self::FULLSCREEN_MODE
+    }
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> u32 {
+    let mut bytes = [0; 4];
+    if let Some(slice) = data.get(offset..offset + 4) {
+        bytes.copy_from_slice(slice);
+    }
+    u32::from_ne_bytes(bytes)
+}
+
+const MAX_GAMEPAD_COUNT: usize = 4;
+const GAMEPAD_BUTTON_COUNT: usize = 17;
+const GAMEPAD_AXIS_COUNT: usize = 6;
+
+#[derive(Debug, Default)]
+pub(crate) struct StdGamepadsState {
+    pads: [Gamepad; MAX_GAMEPAD_COUNT],
+}
+
+impl StdGamepadsState {
+    pub(crate) fn data(&self) -> Vec<u8> {
+        self.pads.iter().flat_map(Gamepad::data).collect()
+    }
+
+    pub(crate) fn update_pad(&mut self, event: GamepadEvent) {
+        match event {
+            GamepadEvent::Connected(id) => {
+                if self.existing_pad(id).is_none() {
+                    if let Some(pad) = self.pads.iter_mut().find(|pad| pad.id.is_none()) {
+                        *pad = Gamepad {
+                            id: Some(id),
+                            ..Gamepad::default()
+                        };
+                    }
+                }
+            }
+            GamepadEvent::Disconnected(id) => {
+                if let Some(pad) = self.existing_pad(id) {
+                    *pad = Gamepad::default();
+                }
+            }
+            GamepadEvent::ButtonPressed(id, button) => {
+                if let Some(pad) = self.existing_pad(id) {
+                    pad.buttons[button as usize].press();
+                }
+            }
+            GamepadEvent::ButtonReleased(id, button) => {
+                if let Some(pad) = self.existing_pad(id) {
+                    pad.buttons[button as usize].release();
+                }
+            }
+            GamepadEvent::AxisChanged(id, axis, value) => {
+                if let Some(pad) = self.existing_pad(id) {
+                    let min_value = match axis {
+                        GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => 0.,
+                        GamepadAxis::LeftStickX
+                        | GamepadAxis::LeftStickY
+                        | GamepadAxis::RightStickX
+                        | GamepadAxis::RightStickY => -1.,
+                    };
+                    pad.axes[axis as usize] = value.clamp(min_value, 1.);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        for pad in &mut self.pads {
+            for button in &mut pad.buttons {
+                button.refresh();
+            }
+        }
+    }
+
+    fn existing_pad(&mut self, id: u32) -> Option<&mut Gamepad> {
+        self.pads.iter_mut().find(|pad| pad.id == Some(id))
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Gamepad {
+    id: Option<u32>,
+    buttons: [InputState; GAMEPAD_BUTTON_COUNT],
+    axes: [f32; GAMEPAD_AXIS_COUNT],
+}
+
+impl Gamepad {
+    fn data(&self) -> Vec<u8> {
+        u32::from(self.id.is_some())
+            .to_ne_bytes()
+            .into_iter()
+            .chain(self.buttons.iter().flat_map(|state| state.data()))
+            .chain(self.axes.iter().flat_map(|axis| axis.to_ne_bytes()))
+            .collect()
+    }
+}
 
 const KEYBOARD_KEY_COUNT: usize = KeyCode::F35 as usize;
+const MAX_TEXT_INPUT_LENGTH: usize = 32;
 
 #[derive(Debug)]
 pub(crate) struct StdKeyboardState {
     keys: [InputState; KEYBOARD_KEY_COUNT],
+    logical_keys: [InputState; KEYBOARD_KEY_COUNT],
+    text: Vec<char>,
+    is_ime_enabled: bool,
 }
 
 impl Default for StdKeyboardState {
     fn default() -> Self {
         Self {
             keys: [InputState::default(); KEYBOARD_KEY_COUNT],
+            logical_keys: [InputState::default(); KEYBOARD_KEY_COUNT],
+            text: vec![],
+            is_ime_enabled: false,
         }
     }
 }
 
 impl StdKeyboardState {
+    #[allow(clippy::cast_possible_truncation)]
     pub(crate) fn data(&self) -> Vec<u8> {
-        self.keys.iter().flat_map(|state| state.data()).collect()
+        self.keys
+            .iter()
+            .chain(&self.logical_keys)
+            .flat_map(|state| state.data())
+            .chain((self.text.len() as u32).to_ne_bytes())
+            .chain(
+                self.text
+                    .iter()
+                    .map(|&char| u32::from(char))
+                    .chain(iter::repeat(0))
+                    .take(MAX_TEXT_INPUT_LENGTH)
+                    .flat_map(u32::to_ne_bytes),
+            )
+            .collect()
     }
 
-    pub(crate) fn update_key(&mut self, key: KeyCode, state: ElementState) {
-        let key_index = key as usize;
-        match state {
-            ElementState::Pressed => self.keys[key_index].press(),
-            ElementState::Released => self.keys[key_index].release(),
+    pub(crate) fn update_key(&mut self, event: &KeyEvent) {
+        self.update_key_input(
+            event.physical_key,
+            &event.logical_key,
+            event.state,
+            event.text.as_deref(),
+        );
+    }
+
+    /// Sets whether text is received from IME instead of key events.
+    ///
+    /// Text of key events is ignored while IME is enabled to avoid adding it twice,
+    /// as committed IME text is added with [`StdKeyboardState::add_text`].
+    pub(crate) fn set_ime_enabled(&mut self, is_enabled: bool) {
+        self.is_ime_enabled = is_enabled;
+    }
+
+    fn update_key_input(
+        &mut self,
+        physical_key: PhysicalKey,
+        logical_key: &Key,
+        state: ElementState,
+        text: Option<&str>,
+    ) {
+        let physical_key = match physical_key {
+            PhysicalKey::Code(key) => Some(key),
+            PhysicalKey::Unidentified(_) => None,
+        };
+        if let Some(key) = physical_key {
+            Self::update_key_state(&mut self.keys[key as usize], state);
+        }
+        if let Some(key) = Self::logical_key_code(logical_key).or(physical_key) {
+            Self::update_key_state(&mut self.logical_keys[key as usize], state);
+        }
+        if state == ElementState::Pressed && !self.is_ime_enabled {
+            if let Some(text) = text {
+                self.add_text(text);
+            }
         }
     }
 
+    pub(crate) fn add_text(&mut self, text: &str) {
+        let available_length = MAX_TEXT_INPUT_LENGTH.saturating_sub(self.text.len());
+        self.text.extend(
+            text.chars()
+                .filter(|char| !char.is_control())
+                .take(available_length),
+        );
+    }
+
     fn update(&mut self) {
-        for key in &mut self.keys {
+        for key in self.keys.iter_mut().chain(&mut self.logical_keys) {
             key.refresh();
         }
+        self.text.clear();
+    }
+
+    fn update_key_state(key: &mut InputState, state: ElementState) {
+        match state {
+            ElementState::Pressed => key.press(),
+            ElementState::Released => key.release(),
+        }
+    }
+
+    fn logical_key_code(key: &Key) -> Option<KeyCode> {
+        let Key::Character(text) = key else {
+            return None;
+        };
+        let mut chars = text.chars().flat_map(char::to_lowercase);
+        let (Some(char), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        Some(match char {
+            'a' => KeyCode::KeyA,
+            'b' => KeyCode::KeyB,
+            'c' => KeyCode::KeyC,
+            'd' => KeyCode::KeyD,
+            'e' => KeyCode::KeyE,
+            'f' => KeyCode::KeyF,
+            'g' => KeyCode::KeyG,
+            'h' => KeyCode::KeyH,
+            'i' => KeyCode::KeyI,
+            'j' => KeyCode::KeyJ,
+            'k' => KeyCode::KeyK,
+            'l' => KeyCode::KeyL,
+            'm' => KeyCode::KeyM,
+            'n' => KeyCode::KeyN,
+            'o' => KeyCode::KeyO,
+            'p' => KeyCode::KeyP,
+            'q' => KeyCode::KeyQ,
+            'r' => KeyCode::KeyR,
+            's' => KeyCode::KeyS,
+            't' => KeyCode::KeyT,
+            'u' => KeyCode::KeyU,
+            'v' => KeyCode::KeyV,
+            'w' => KeyCode::KeyW,
+            'x' => KeyCode::KeyX,
+            'y' => KeyCode::KeyY,
+            'z' => KeyCode::KeyZ,
+            '0' => KeyCode::Digit0,
+            '1' => KeyCode::Digit1,
+            '2' => KeyCode::Digit2,
+            '3' => KeyCode::Digit3,
+            '4' => KeyCode::Digit4,
+            '5' => KeyCode::Digit5,
+            '6' => KeyCode::Digit6,
+            '7' => KeyCode::Digit7,
+            '8' => KeyCode::Digit8,
+            '9' => KeyCode::Digit9,
+            '`' => KeyCode::Backquote,
+            '-' => KeyCode::Minus,
+            '=' => KeyCode::Equal,
+            '[' => KeyCode::BracketLeft,
+            ']' => KeyCode::BracketRight,
+            '\\' => KeyCode::Backslash,
+            ';' => KeyCode::Semicolon,
+            '\'' => KeyCode::Quote,
+            ',' => KeyCode::Comma,
+            '.' => KeyCode::Period,
+            '/' => KeyCode::Slash,
+            ' ' => KeyCode::Space,
+            _ => return None,
+        })
     }
 }
 
+// coverage: off (not easy to test)
+
 const MOUSE_BUTTON_COUNT: usize = 5;
 const MOUSE_MAX_SPECIAL_BUTTON_COUNT: usize = 32;
 
@@ -389,3 +720,140 @@ impl InputState {
         self.data &= !(1 << position);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::std::{StdKeyboardState, StdWindowState, MAX_TEXT_INPUT_LENGTH};
+    use winit::event::ElementState;
+    use winit::keyboard::{Key, KeyCode, NamedKey, NativeKeyCode, PhysicalKey};
+
+    #[test]
+    fn decode_window_state() {
+        let data: Vec<u8> = ['H' as u32, 'i' as u32, 0, 'x' as u32, 800, 600, 1, 1, 0, 1]
+            .into_iter()
+            .flat_map(u32::to_ne_bytes)
+            .collect();
+        let state = StdWindowState::new(&data, |name| match name {
+            "title" => Some((0, 16)),
+            "size" => Some((16, 8)),
+            "mode" => Some((24, 4)),
+            "is_cursor_hidden" => Some((28, 4)),
+            "is_cursor_grabbed" => Some((32, 4)),
+            "is_vsync_disabled" => Some((36, 4)),
+            _ => None,
+        });
+        assert_eq!(
+            state,
+            StdWindowState {
+                title: "Hi".into(),
+                size: (800, 600),
+                mode: StdWindowState::FULLSCREEN_MODE,
+                is_cursor_hidden: true,
+                is_cursor_grabbed: false,
+                is_vsync_disabled: true,
+            }
+        );
+        assert!(state.is_fullscreen());
+    }
+
+    #[test]
+    fn decode_window_state_with_missing_fields() {
+        let data: Vec<u8> = ['a' as u32, 'b' as u32, 'c' as u32]
+            .into_iter()
+            .flat_map(u32::to_ne_bytes)
+            .collect();
+        let state = StdWindowState::new(&data, |name| (name == "title").then_some((0, 8)));
+        assert_eq!(
+            state,
+            StdWindowState {
+                title: "ab".into(),
+                ..StdWindowState::default()
+            }
+        );
+        assert!(!state.is_fullscreen());
+    }
+
+    #[test]
+    fn map_logical_keys() {
+        let key_code =
+            |text: &str| StdKeyboardState::logical_key_code(&Key::Character(text.into()));
+        assert_eq!(key_code("a"), Some(KeyCode::KeyA));
+        assert_eq!(key_code("A"), Some(KeyCode::KeyA));
+        assert_eq!(key_code("0"), Some(KeyCode::Digit0));
+        assert_eq!(key_code(" "), Some(KeyCode::Space));
+        assert_eq!(key_code("é"), None);
+        assert_eq!(key_code("ab"), None);
+        assert_eq!(
+            StdKeyboardState::logical_key_code(&Key::Named(NamedKey::Enter)),
+            None
+        );
+    }
+
+    #[test]
+    fn update_physical_and_logical_keys() {
+        let mut keyboard = StdKeyboardState::default();
+        let physical_key = PhysicalKey::Code(KeyCode::KeyQ);
+        let logical_key = Key::Character("a".into());
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, None);
+        assert_eq!(keyboard.keys[KeyCode::KeyQ as usize].data, 0b011);
+        assert_eq!(keyboard.logical_keys[KeyCode::KeyA as usize].data, 0b011);
+        assert_eq!(keyboard.logical_keys[KeyCode::KeyQ as usize].data, 0);
+        let logical_key = Key::Named(NamedKey::Enter);
+        let physical_key = PhysicalKey::Code(KeyCode::Enter);
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, None);
+        assert_eq!(keyboard.logical_keys[KeyCode::Enter as usize].data, 0b011);
+        let physical_key = PhysicalKey::Unidentified(NativeKeyCode::Unidentified);
+        let logical_key = Key::Character("b".into());
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, None);
+        assert_eq!(keyboard.logical_keys[KeyCode::KeyB as usize].data, 0b011);
+        keyboard.update();
+        let physical_key = PhysicalKey::Code(KeyCode::KeyQ);
+        let logical_key = Key::Character("a".into());
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Released, None);
+        assert_eq!(keyboard.keys[KeyCode::KeyQ as usize].data, 0b100);
+        assert_eq!(keyboard.logical_keys[KeyCode::KeyA as usize].data, 0b100);
+    }
+
+    #[test]
+    fn update_text() {
+        let mut keyboard = StdKeyboardState::default();
+        let physical_key = PhysicalKey::Code(KeyCode::KeyA);
+        let logical_key = Key::Character("a".into());
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, Some("a"));
+        keyboard.update_key_input(
+            physical_key,
+            &logical_key,
+            ElementState::Released,
+            Some("a"),
+        );
+        keyboard.add_text("b\n\u{8}c");
+        assert_eq!(keyboard.text, ['a', 'b', 'c']);
+        let data = keyboard.data();
+        let text_offset = data.len() - (MAX_TEXT_INPUT_LENGTH + 1) * 4;
+        assert_eq!(data[text_offset..text_offset + 4], 3_u32.to_ne_bytes());
+        assert_eq!(
+            data[text_offset + 4..text_offset + 8],
+            u32::from('a').to_ne_bytes()
+        );
+        assert_eq!(data[text_offset + 16..text_offset + 20], [0; 4]);
+        keyboard.add_text(&"d".repeat(MAX_TEXT_INPUT_LENGTH));
+        assert_eq!(keyboard.text.len(), MAX_TEXT_INPUT_LENGTH);
+        keyboard.update();
+        assert!(keyboard.text.is_empty());
+    }
+
+    #[test]
+    fn update_text_with_ime() {
+        let mut keyboard = StdKeyboardState::default();
+        keyboard.set_ime_enabled(true);
+        let physical_key = PhysicalKey::Code(KeyCode::KeyA);
+        let logical_key = Key::Character("a".into());
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, Some("a"));
+        keyboard.add_text("あ");
+        assert_eq!(keyboard.text, ['あ']);
+        assert_eq!(keyboard.keys[KeyCode::KeyA as usize].data, 0b011);
+        keyboard.set_ime_enabled(false);
+        keyboard.update_key_input(physical_key, &logical_key, ElementState::Pressed, Some("a"));
+        assert_eq!(keyboard.text, ['あ', 'a']);
+    }
+}
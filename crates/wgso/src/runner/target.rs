@@ -1,5 +1,8 @@
 use std::sync::Arc;
-use wgpu::{Surface, SurfaceConfiguration, SurfaceTexture, Texture, TextureFormat, TextureView};
+use wgpu::{
+    Adapter, Instance, Surface, SurfaceConfiguration, SurfaceTexture, Texture, TextureFormat,
+    TextureView,
+};
 use winit::window::Window;
 
 #[derive(Debug)]
@@ -13,9 +16,10 @@ impl Target {
     pub(crate) fn texture_format(&self) -> TextureFormat {
         match &self.inner {
             // coverage: off (window cannot be tested)
-            TargetSpecialized::Window(target) => target.surface_config.format,
+            TargetSpecialized::Window(target) => target.surface_config.format.add_srgb_suffix(),
             // coverage: on
             TargetSpecialized::Texture(_) => TextureFormat::Rgba8UnormSrgb,
+            TargetSpecialized::External(format) => *format,
         }
     }
 }
@@ -24,6 +28,7 @@ impl Target {
 pub(crate) enum TargetSpecialized {
     Window(WindowTarget),
     Texture(TextureTarget),
+    External(TextureFormat),
 }
 
 #[derive(Debug)]
@@ -39,6 +44,8 @@ pub(crate) struct WindowTarget {
     pub(crate) window: Arc<Window>,
     pub(crate) surface: Surface<'static>,
     pub(crate) surface_config: SurfaceConfiguration,
+    pub(crate) instance: Instance,
+    pub(crate) adapter: Adapter,
 }
 
 impl WindowTarget {
@@ -4,6 +4,9 @@ pub(crate) struct RunnerWatcher {
     _watcher: notify::RecommendedWatcher,
     watcher_events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
     next_update: Option<web_time::Instant>,
+    folder_path: std::path::PathBuf,
+    canonical_folder_path: std::path::PathBuf,
+    changed_paths: fxhash::FxHashSet<std::path::PathBuf>,
 }
 
 #[cfg(all(not(target_os = "android"), not(target_arch = "wasm32")))]
@@ -22,14 +25,29 @@ impl RunnerWatcher {
             _watcher: watcher,
             watcher_events: rx,
             next_update: None,
+            folder_path: folder_path.into(),
+            canonical_folder_path: std::fs::canonicalize(folder_path)
+                .unwrap_or_else(|_| folder_path.into()),
+            changed_paths: fxhash::FxHashSet::default(),
         }
     }
 
-    pub(crate) fn detect_changes(&mut self) -> bool {
-        let is_updated = self.watcher_events.try_iter().any(|event| match event {
-            Ok(event) => event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove(),
-            Err(_) => false, // no-coverage (not easy to test)
+    /// Returns the paths of the files changed since last detected changes, if any.
+    pub(crate) fn detect_changes(&mut self) -> Option<fxhash::FxHashSet<std::path::PathBuf>> {
+        let mut is_updated = false;
+        let events = self.watcher_events.try_iter().flatten().filter(|event| {
+            event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()
         });
+        for event in events {
+            is_updated = true;
+            for path in event.paths {
+                let path = match path.strip_prefix(&self.canonical_folder_path) {
+                    Ok(relative_path) => self.folder_path.join(relative_path),
+                    Err(_) => path,
+                };
+                self.changed_paths.insert(path);
+            }
+        }
         if is_updated {
             self.next_update = Some(
                 web_time::Instant::now()
@@ -41,9 +59,9 @@ impl RunnerWatcher {
             .is_some_and(|next_update| web_time::Instant::now() >= next_update)
         {
             self.next_update = None;
-            true
+            Some(std::mem::take(&mut self.changed_paths))
         } else {
-            false
+            None
         }
     }
 }
@@ -59,7 +77,7 @@ impl RunnerWatcher {
     }
 
     #[allow(clippy::unused_self, clippy::needless_pass_by_ref_mut)]
-    pub(crate) fn detect_changes(&mut self) -> bool {
-        false
+    pub(crate) fn detect_changes(&mut self) -> Option<fxhash::FxHashSet<std::path::PathBuf>> {
+        None
     }
 }
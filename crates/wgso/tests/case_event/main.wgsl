@@ -0,0 +1,13 @@
+#mod main
+#import _.std.io.compute
+#run ~.update()
+
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    send_event(EVENT_EXIT, array(1u, 2u, 3u, 4u));
+    send_event(EVENT_CUSTOM + 1, array(std_.time.frame_index, 0u, 0u, 0u));
+}
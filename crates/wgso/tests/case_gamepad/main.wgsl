@@ -0,0 +1,28 @@
+#mod main
+#import _.std.input.gamepad
+#import _.std.io.compute
+#run ~.update()
+
+var<storage, read_write> pad: Pad;
+
+struct Pad {
+    is_connected: u32,
+    south_button: InputState,
+    left_stick_x: f32,
+    left_trigger: f32,
+}
+
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    let gamepad = std_.gamepads.pads[0];
+    pad = Pad(
+        gamepad.is_connected,
+        gamepad.buttons[GP_BUTTON_SOUTH],
+        gamepad.axes[GP_AXIS_LEFT_STICK_X],
+        gamepad.axes[GP_AXIS_LEFT_TRIGGER],
+    );
+}
@@ -0,0 +1,13 @@
+#mod main
+#run ~.update()
+
+var<storage, read_write> total: u32;
+
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    total = subgroupAdd(1u);
+}
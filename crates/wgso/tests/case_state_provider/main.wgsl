@@ -0,0 +1,19 @@
+#mod main
+#run ~.update()
+
+var<storage, read_write> network: Network;
+var<storage, read_write> total: u32;
+
+struct Network {
+    value: u32,
+    factor: u32,
+}
+
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    total += network.value * network.factor;
+}
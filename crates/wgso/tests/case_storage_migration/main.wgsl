@@ -0,0 +1,37 @@
+#mod main
+#init ~.init()
+#run ~.update()
+
+var<storage, read_write> state: State;
+var<storage, read_write> items: array<Item, 2>;
+var<storage, read_write> removed: u32;
+
+struct State {
+    frame: u32,
+    removed: u32,
+    speed: f32,
+}
+
+struct Item {
+    value: u32,
+}
+
+#shader<compute> init
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    items[0].value = 5;
+    items[1].value = 6;
+}
+
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    state.frame += 1;
+    state.speed = 2;
+}
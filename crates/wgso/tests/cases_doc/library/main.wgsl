@@ -0,0 +1,23 @@
+/// Main module of the library.
+///
+/// It gathers the shared constants.
+#mod main
+#import ~.~.shape.circle.main
+
+/// The maximum number of circles.
+const MAX_CIRCLE_COUNT = 16;
+
+/// Circles displayed on screen.
+var<storage, read_write> circles: array<Circle, MAX_CIRCLE_COUNT>;
+
+/// Compute shader moving the circles.
+#shader<compute> update
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    for (var i = 0u; i < MAX_CIRCLE_COUNT; i++) {
+        circles[i].center += vec2f(0.01, 0.);
+    }
+}
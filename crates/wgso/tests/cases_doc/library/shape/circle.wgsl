@@ -0,0 +1,44 @@
+/// Circle utilities.
+#mod main
+
+/// The circle type.
+alias CircleRadius = f32;
+
+/// A circle.
+struct Circle {
+    /// The center position.
+    center: vec2f,
+    /// The radius.
+    ///
+    /// Negative radius is considered as zero.
+    radius: CircleRadius,
+    _padding: vec2f,
+}
+
+/// The bounding box of a circle.
+struct CircleBounds { min: vec2f, /* inclusive */ max: vec2f } // see `circle_bounds` // computation
+
+/// The vertex of a rendered circle.
+struct CircleVertex {
+    @builtin(position)
+    position: vec4f,
+    /// The index of the circle.
+    @location(0) @interpolate(flat, either) index: u32,
+    /// The corners of the circle bounds.
+    @location(1) corners: mat2x2<f32>, // min, max
+}
+
+/// Returns the area of a `circle`.
+fn circle_area(circle: Circle) -> f32 {
+    const PI = 3.14159265359;
+    return PI * max(circle.radius, 0.) * max(circle.radius, 0.);
+}
+
+fn _private_helper() -> f32 {
+    return 0.;
+}
+
+/// Returns the bounding box of a `circle`.
+fn circle_bounds(circle: Circle) -> CircleBounds {
+    return CircleBounds(circle.center - circle.radius, circle.center + circle.radius);
+}
@@ -0,0 +1,39 @@
+#mod main
+#init ~.init()
+#draw main.render<vertices, instances>()
+#toggle<is_paused> scenes.paused
+
+var<storage, read_write> speed: f32;
+var<storage, read_write> is_paused: u32;
+var<storage, read_write> vertices: array<Vertex, 3>;
+var<storage, read_write> instances: array<Instance, 1>;
+
+struct Vertex {
+    position: vec2f,
+}
+
+struct Instance {
+    position: vec2f,
+}
+
+#shader<compute> init
+#import ~.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    speed = 0.01;
+}
+
+#shader<render, Vertex, Instance> render
+#import ~.main
+
+@vertex
+fn vs_main(vertex: Vertex, instance: Instance) -> @builtin(position) vec4f {
+    return vec4f(vertex.position + instance.position, 0., 1.);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4f {
+    return vec4f(1., 1., 1., 1.);
+}
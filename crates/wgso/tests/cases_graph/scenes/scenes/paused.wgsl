@@ -0,0 +1,11 @@
+#mod main
+#run<-5> ~.blink()
+
+#shader<compute> blink
+#import main.main
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    instances[0].position.y = -instances[0].position.y;
+}
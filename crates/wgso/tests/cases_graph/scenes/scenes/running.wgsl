@@ -0,0 +1,13 @@
+#mod main
+#run<10> ~.update(step=speed)
+
+#shader<compute> update
+#import main.main
+
+var<uniform> step: f32;
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    instances[0].position.x += step * f32(1 - is_paused);
+}
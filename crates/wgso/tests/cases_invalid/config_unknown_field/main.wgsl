@@ -0,0 +1,4 @@
+#mod main
+#toggle<is_enabled> toggled
+
+var<storage, read_write> is_enabled: u32;
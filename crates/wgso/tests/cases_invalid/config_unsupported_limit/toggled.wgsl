@@ -0,0 +1,13 @@
+#mod toggled
+#run ~.update()
+
+var<storage, read_write> counter: u32;
+
+#shader<compute> update
+#import ~.toggled
+
+@compute
+@workgroup_size(1, 1, 1)
+fn main() {
+    counter += 1;
+}
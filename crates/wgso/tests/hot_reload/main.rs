@@ -4,7 +4,7 @@ use std::path::Path;
 use std::sync::Mutex;
 use std::time::Duration;
 use std::{fs, thread};
-use wgso::{Program, Runner};
+use wgso::{Program, Runner, StorageMigration};
 
 const EXPECTED_DEFAULT_TARGET: &[u8] = &[
     0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, // row 1
@@ -16,8 +16,18 @@ const EXPECTED_CHANGED_TARGET: &[u8] = &[
     0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, // row 2
     0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, // row 3
 ];
+const EXPECTED_OVERLAY_TARGET: &[u8] = &[
+    0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, // row 1
+    0, 0, 0, 255, 108, 108, 108, 255, 108, 108, 108, 255, 0, 0, 0, 255, // row 2
+    0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255, // row 3
+];
 const PROGRAM_PATH: &str = "tests/case_hot_reload";
 const PROGRAM_WGSL_PATH: &str = "tests/case_hot_reload/main.wgsl";
+const NEW_PROGRAM_WGSL_PATH: &str = "tests/case_hot_reload/moved.wgsl";
+const MIGRATION_PROGRAM_PATH: &str = "tests/case_storage_migration";
+const MIGRATION_PROGRAM_WGSL_PATH: &str = "tests/case_storage_migration/main.wgsl";
+const CONFIG_PROGRAM_PATH: &str = "tests/case_config_reload";
+const CONFIG_PATH: &str = "tests/case_config_reload/wgso.yaml";
 
 static MUTEX: Mutex<()> = Mutex::new(());
 
@@ -38,6 +48,22 @@ fn reload_with_valid_program() {
     assert_eq!(runner.read_target(), EXPECTED_CHANGED_TARGET);
 }
 
+#[test]
+fn reload_with_new_file() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read_target(), EXPECTED_DEFAULT_TARGET);
+    let code = "#mod moved\n#run ~.move()\n\n#shader<compute> move\n#import main.storage\n\n\
+        @compute\n@workgroup_size(1, 1, 1)\nfn main() {\n    state.instance.position = vec2f(10, 10);\n}\n";
+    let reloading_result = update_code(&mut runner, code, NEW_PROGRAM_WGSL_PATH);
+    let run_result = runner.run_step();
+    fs::remove_file(NEW_PROGRAM_WGSL_PATH).unwrap();
+    assert!(reloading_result.unwrap().is_none());
+    assert!(run_result.is_ok());
+    assert_eq!(runner.read_target(), EXPECTED_CHANGED_TARGET);
+}
+
 #[test]
 fn reload_with_invalid_program() {
     let _lock = MUTEX.lock().unwrap();
@@ -52,6 +78,45 @@ fn reload_with_invalid_program() {
     assert_eq!(runner.read_target(), EXPECTED_DEFAULT_TARGET);
 }
 
+#[test]
+fn reload_after_failure_in_other_file() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    let initial_code = fs::read_to_string(PROGRAM_WGSL_PATH).unwrap();
+    let modified_code = initial_code.replace("vec4f(1, 1, 1, 1)", "vec4f(0, 0, 0, 1)");
+    fs::write(PROGRAM_WGSL_PATH, modified_code).unwrap();
+    let invalid_reloading_result =
+        update_code(&mut runner, "#mod moved\ninvalid", NEW_PROGRAM_WGSL_PATH);
+    let valid_reloading_result = update_code(&mut runner, "#mod moved\n", NEW_PROGRAM_WGSL_PATH);
+    let run_result = runner.run_step();
+    fs::remove_file(NEW_PROGRAM_WGSL_PATH).unwrap();
+    fs::write(PROGRAM_WGSL_PATH, initial_code).unwrap();
+    assert!(invalid_reloading_result.is_err());
+    assert!(valid_reloading_result.is_ok());
+    assert!(run_result.is_ok());
+    assert_eq!(runner.read_target(), EXPECTED_CHANGED_TARGET);
+}
+
+#[test]
+fn reload_with_error_overlay() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.enable_error_overlay();
+    let initial_code = fs::read_to_string(PROGRAM_WGSL_PATH).unwrap();
+    let modified_code = initial_code.replace("vec4f(1, 1, 1, 1)", "vec4f(1, 1, 1)");
+    let invalid_reloading_result = update_code(&mut runner, &modified_code, PROGRAM_WGSL_PATH);
+    let invalid_run_result = runner.run_step().is_ok();
+    let invalid_target = runner.read_target();
+    let valid_reloading_result = update_code(&mut runner, &initial_code, PROGRAM_WGSL_PATH);
+    let valid_run_result = runner.run_step().is_ok();
+    assert!(invalid_reloading_result.is_err());
+    assert!(invalid_run_result);
+    assert_eq!(invalid_target, EXPECTED_OVERLAY_TARGET);
+    assert!(valid_reloading_result.is_ok());
+    assert!(valid_run_result);
+    assert_eq!(runner.read_target(), EXPECTED_DEFAULT_TARGET);
+}
+
 #[test]
 fn reload_with_wgpu_error() {
     let _lock = MUTEX.lock().unwrap();
@@ -71,29 +136,108 @@ fn reload_with_wgpu_error() {
 fn reload_with_changed_storage() {
     let _lock = MUTEX.lock().unwrap();
     let mut runner = Runner::new(Path::new(PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.run_step().unwrap();
     let initial_code = fs::read_to_string(PROGRAM_WGSL_PATH).unwrap();
     let modified_code = initial_code.replace("State", "ModifiedState");
     let reloading_result = update_code(&mut runner, &modified_code, PROGRAM_WGSL_PATH);
     let run_result = runner.run_step();
     fs::write(PROGRAM_WGSL_PATH, initial_code).unwrap();
+    let migration = reloading_result.unwrap().unwrap();
+    assert_eq!(migration.copied, ["state.vertices", "state.instance"]);
+    assert!(run_result.is_ok());
+    assert_eq!(runner.read_target(), EXPECTED_DEFAULT_TARGET);
+}
+
+#[test]
+fn reload_with_migrated_storage() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(MIGRATION_PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.run_step().unwrap();
+    runner.run_step().unwrap();
+    let initial_code = fs::read_to_string(MIGRATION_PROGRAM_WGSL_PATH).unwrap();
+    let modified_code = initial_code
+        .replace("var<storage, read_write> removed: u32;\n", "")
+        .replace("    removed: u32,\n", "")
+        .replace("    frame: u32,", "    added: vec2f,\n    frame: u32,")
+        .replace("speed: f32", "speed: i32")
+        .replace("array<Item, 2>", "array<Item, 3>")
+        .replace("    value: u32,", "    id: u32,\n    value: u32,")
+        .replace("    items[0].value = 5;\n    items[1].value = 6;\n", "");
+    let reloading_result = update_code(&mut runner, &modified_code, MIGRATION_PROGRAM_WGSL_PATH);
+    let run_result = runner.run_step();
+    fs::write(MIGRATION_PROGRAM_WGSL_PATH, initial_code).unwrap();
+    let migration = reloading_result.unwrap().unwrap();
+    assert!(run_result.is_ok());
+    assert_eq!(migration.copied, ["items.value", "state.frame"]);
+    assert_eq!(migration.added, ["items.id", "state.added"]);
+    assert_eq!(migration.removed, ["state.removed", "removed"]);
+    assert_eq!(migration.reset, ["state.speed"]);
+    assert_eq!(migration.resized, ["items"]);
     assert_eq!(
-        reloading_result
-            .expect_err("reloading should return an error")
-            .render_errors(),
-        "\u{1b}[1m\u{1b}[91merror\u{1b}[0m: \u{1b}[1mprogram cannot be hot-reloaded because storages have been changed\u{1b}[0m"
+        migration.to_string(),
+        "Storages migrated:\n  \
+        copied: items.value, state.frame\n  \
+        added: items.id, state.added\n  \
+        removed: state.removed, removed\n  \
+        reset: state.speed\n  \
+        resized: items\n"
     );
+    assert_eq!(runner.read("state.frame"), 3_u32.to_ne_bytes());
+    assert_eq!(runner.read("state.added"), [0; 8]);
+    assert_eq!(runner.read("state.speed"), 2_i32.to_ne_bytes());
+    assert_eq!(
+        runner.read("items"),
+        [0_u32, 5, 0, 6, 0, 0]
+            .into_iter()
+            .flat_map(u32::to_ne_bytes)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reload_with_changed_config() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(CONFIG_PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read("is_enabled"), 0_u32.to_ne_bytes());
+    let initial_config = fs::read_to_string(CONFIG_PATH).unwrap();
+    let modified_config = format!("{initial_config}  enabled_toggles: [is_enabled]\n");
+    let reloading_result = update_code(&mut runner, &modified_config, CONFIG_PATH);
+    let run_result = runner.run_step();
+    fs::write(CONFIG_PATH, initial_config).unwrap();
+    assert!(reloading_result.is_ok());
     assert!(run_result.is_ok());
-    assert_eq!(runner.read_target(), EXPECTED_DEFAULT_TARGET);
+    assert_eq!(runner.read("is_enabled"), 1_u32.to_ne_bytes());
+    assert_eq!(runner.read("counter"), 1_u32.to_ne_bytes());
+}
+
+#[test]
+fn reload_with_unsupported_config() {
+    let _lock = MUTEX.lock().unwrap();
+    let mut runner = Runner::new(Path::new(CONFIG_PROGRAM_PATH), None, Some((4, 3))).unwrap();
+    runner.run_step().unwrap();
+    let initial_config = fs::read_to_string(CONFIG_PATH).unwrap();
+    let modified_config = format!("{initial_config}  features: [subgroup]\n");
+    let reloading_result = update_code(&mut runner, &modified_config, CONFIG_PATH);
+    fs::write(CONFIG_PATH, initial_config).unwrap();
+    let program = reloading_result.unwrap_err();
+    assert!(program
+        .render_errors()
+        .contains("GPU features not supported by the device: SUBGROUP"));
 }
 
 #[allow(clippy::result_large_err)]
-fn update_code(runner: &mut Runner, code: &str, path: &str) -> Result<(), Program> {
+fn update_code(
+    runner: &mut Runner,
+    code: &str,
+    path: &str,
+) -> Result<Option<StorageMigration>, Program> {
     fs::write(path, code).unwrap();
-    runner.reload_on_change()?;
+    let mut migration = runner.reload_on_change()?;
     thread::sleep(Duration::from_millis(100));
-    runner.reload_on_change()?;
+    migration = runner.reload_on_change()?.or(migration);
     thread::sleep(Duration::from_millis(100));
-    runner.reload_on_change()?;
+    migration = runner.reload_on_change()?.or(migration);
     thread::sleep(Duration::from_secs(2));
-    runner.reload_on_change()
+    Ok(runner.reload_on_change()?.or(migration))
 }
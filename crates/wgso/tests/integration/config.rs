@@ -0,0 +1,25 @@
+use std::path::Path;
+use wgso::{Program, Runner};
+
+#[test]
+fn run_with_program_config() {
+    let mut runner = Runner::new(Path::new("tests/case_program_config"), None, None).unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read("is_enabled"), vec![1, 0, 0, 0]);
+    assert_eq!(runner.read("counter"), vec![1, 0, 0, 0]);
+    assert_eq!(runner.read_target().len(), 4 * 3 * 4);
+}
+
+#[test]
+fn run_with_explicit_size() {
+    let mut runner =
+        Runner::new(Path::new("tests/case_program_config"), None, Some((2, 2))).unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read_target().len(), 2 * 2 * 4);
+}
+
+#[test]
+fn parse_program_with_required_feature() {
+    let program = Program::parse(Path::new("tests/case_program_feature"));
+    assert!(program.errors.is_empty());
+}
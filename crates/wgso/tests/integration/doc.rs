@@ -0,0 +1,57 @@
+use clap::Parser;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use wgso::Args;
+
+#[rstest::rstest]
+fn generate_doc(#[files("./tests/cases_doc/*")] path: PathBuf) {
+    let output_path = std::env::temp_dir()
+        .join("wgso_doc")
+        .join(path.file_name().unwrap());
+    if output_path.is_dir() {
+        fs::remove_dir_all(&output_path).unwrap();
+    }
+    Args::parse_from([
+        "wgso",
+        "doc",
+        path.to_str().unwrap(),
+        "--output",
+        output_path.to_str().unwrap(),
+    ])
+    .run();
+    let expected_path = path.join(".expected");
+    if expected_path.exists() {
+        assert_eq!(
+            relative_file_paths(&expected_path),
+            relative_file_paths(&output_path),
+            "mismatching pages for {:?} case",
+            path.file_stem().unwrap(),
+        );
+        for file_path in relative_file_paths(&expected_path) {
+            assert_eq!(
+                fs::read_to_string(expected_path.join(&file_path)).unwrap(),
+                fs::read_to_string(output_path.join(&file_path)).unwrap(),
+                "mismatching {file_path:?} page for {:?} case",
+                path.file_stem().unwrap(),
+            );
+        }
+    } else {
+        for file_path in relative_file_paths(&output_path) {
+            fs::create_dir_all(expected_path.join(&file_path).parent().unwrap()).unwrap();
+            fs::copy(output_path.join(&file_path), expected_path.join(&file_path)).unwrap();
+        }
+        panic!("expected pages saved on disk, please check and rerun the tests");
+    }
+}
+
+fn relative_file_paths(path: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = WalkDir::new(path)
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|entry| !entry.file_type().is_dir())
+        .map(|entry| entry.path().strip_prefix(path).unwrap().to_path_buf())
+        .collect();
+    paths.sort_unstable();
+    paths
+}
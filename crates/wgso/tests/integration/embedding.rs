@@ -0,0 +1,150 @@
+use futures::executor;
+use std::path::Path;
+use wgpu::{
+    BufferDescriptor, BufferUsages, Color, CommandEncoderDescriptor, Device, DeviceDescriptor,
+    Extent3d, Instance, LoadOp, MapMode, Operations, PollType, Queue, RenderPassColorAttachment,
+    RenderPassDescriptor, RequestAdapterOptions, StoreOp, TexelCopyBufferInfo,
+    TexelCopyBufferLayout, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsages, TextureViewDescriptor,
+};
+use wgso::{Error, Runner};
+
+const SIZE: (u32, u32) = (4, 3);
+
+#[test]
+fn run_with_external_device() {
+    let (device, queue) = create_device();
+    let texture = create_texture(&device);
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    let mut runner = Runner::with_device(
+        Path::new("tests/case_embedding"),
+        &device,
+        &queue,
+        TextureFormat::Rgba8UnormSrgb,
+        SIZE,
+    )
+    .unwrap();
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.begin_render_pass(&RenderPassDescriptor {
+        label: None,
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: &view,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(Color::RED),
+                store: StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    runner.run_step_into(&mut encoder, &view, None).unwrap();
+    queue.submit(Some(encoder.finish()));
+    assert!(runner.read_target().is_empty());
+    assert_eq!(
+        read_texture(&device, &queue, &texture),
+        [
+            255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, // row 1
+            255, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 255, // row 2
+            255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 0, 0, 255, // row 3
+        ]
+    );
+}
+
+#[test]
+fn run_step_with_external_device() {
+    let (device, queue) = create_device();
+    let mut runner = Runner::with_device(
+        Path::new("tests/case_embedding"),
+        &device,
+        &queue,
+        TextureFormat::Rgba8UnormSrgb,
+        SIZE,
+    )
+    .unwrap();
+    let program = runner.run_step().unwrap_err();
+    assert!(matches!(
+        program.errors[..],
+        [Error::UnsupportedOperation(_)]
+    ));
+    assert!(program
+        .render_errors()
+        .contains("runner with external device should be run with `run_step_into`"));
+}
+
+#[test]
+fn create_runner_with_external_device_missing_features() {
+    let (device, queue) = create_device();
+    let result = Runner::with_device(
+        Path::new("tests/case_program_feature"),
+        &device,
+        &queue,
+        TextureFormat::Rgba8UnormSrgb,
+        SIZE,
+    );
+    let program = result.unwrap_err();
+    assert!(matches!(program.errors[..], [Error::InvalidConfig(_, _)]));
+    assert!(program
+        .render_errors()
+        .contains("GPU features not supported by the device: SUBGROUP"));
+}
+
+fn create_device() -> (Device, Queue) {
+    let instance = Instance::default();
+    let adapter =
+        executor::block_on(instance.request_adapter(&RequestAdapterOptions::default())).unwrap();
+    executor::block_on(adapter.request_device(&DeviceDescriptor::default())).unwrap()
+}
+
+fn create_texture(device: &Device) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width: SIZE.0,
+            height: SIZE.1,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: TextureFormat::Rgba8UnormSrgb,
+        usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+fn read_texture(device: &Device, queue: &Queue, texture: &Texture) -> Vec<u8> {
+    let padded_row_bytes = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: None,
+        size: (padded_row_bytes * SIZE.1).into(),
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor::default());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_row_bytes),
+                rows_per_image: None,
+            },
+        },
+        texture.size(),
+    );
+    let submission_index = queue.submit(Some(encoder.finish()));
+    let slice = buffer.slice(..);
+    slice.map_async(MapMode::Read, |_| ());
+    device
+        .poll(PollType::WaitForSubmissionIndex(submission_index))
+        .unwrap();
+    let content = slice
+        .get_mapped_range()
+        .chunks(padded_row_bytes as usize)
+        .flat_map(|row| row[..(SIZE.0 * 4) as usize].to_vec())
+        .collect();
+    content
+}
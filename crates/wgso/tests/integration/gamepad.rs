@@ -0,0 +1,67 @@
+use std::path::Path;
+use wgso::{GamepadAxis, GamepadButton, GamepadEvent, Runner, SyntheticGamepadBackend};
+
+#[test]
+fn update_gamepads() {
+    let path = Path::new("tests/case_gamepad");
+    wgso_deps::retrieve_dependencies(path.join("wgso.yaml")).unwrap();
+    let mut runner = Runner::new(path, None, None).unwrap();
+    let backend = SyntheticGamepadBackend::default();
+    runner.set_gamepad_backend(backend.clone());
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (0, 0, 0.));
+    backend.send(GamepadEvent::ButtonPressed(42, GamepadButton::South));
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (0, 0, 0.));
+    backend.send(GamepadEvent::Connected(42));
+    backend.send(GamepadEvent::ButtonPressed(42, GamepadButton::South));
+    backend.send(GamepadEvent::AxisChanged(42, GamepadAxis::LeftStickX, 1.5));
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (1, 0b011, 1.));
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (1, 0b001, 1.));
+    backend.send(GamepadEvent::ButtonReleased(42, GamepadButton::South));
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (1, 0b100, 1.));
+    backend.send(GamepadEvent::Disconnected(42));
+    runner.run_step().unwrap();
+    assert_eq!(read_pad(&runner), (0, 0, 0.));
+}
+
+#[test]
+fn clamp_gamepad_trigger_axes() {
+    let path = Path::new("tests/case_gamepad");
+    wgso_deps::retrieve_dependencies(path.join("wgso.yaml")).unwrap();
+    let mut runner = Runner::new(path, None, None).unwrap();
+    let backend = SyntheticGamepadBackend::default();
+    runner.set_gamepad_backend(backend.clone());
+    backend.send(GamepadEvent::Connected(42));
+    backend.send(GamepadEvent::AxisChanged(42, GamepadAxis::LeftStickX, -1.5));
+    backend.send(GamepadEvent::AxisChanged(
+        42,
+        GamepadAxis::LeftTrigger,
+        -0.5,
+    ));
+    runner.run_step().unwrap();
+    assert_eq!(read_axes(&runner), (-1., 0.));
+    backend.send(GamepadEvent::AxisChanged(42, GamepadAxis::LeftTrigger, 1.5));
+    runner.run_step().unwrap();
+    assert_eq!(read_axes(&runner), (-1., 1.));
+}
+
+fn read_pad(runner: &Runner) -> (u32, u32, f32) {
+    let data = runner.read("pad");
+    (
+        u32::from_ne_bytes(data[0..4].try_into().unwrap()),
+        u32::from_ne_bytes(data[4..8].try_into().unwrap()),
+        f32::from_ne_bytes(data[8..12].try_into().unwrap()),
+    )
+}
+
+fn read_axes(runner: &Runner) -> (f32, f32) {
+    let data = runner.read("pad");
+    (
+        f32::from_ne_bytes(data[8..12].try_into().unwrap()),
+        f32::from_ne_bytes(data[12..16].try_into().unwrap()),
+    )
+}
@@ -0,0 +1,39 @@
+use clap::Parser;
+use std::fs;
+use std::path::PathBuf;
+use wgso::Args;
+
+#[rstest::rstest]
+fn export_graph(
+    #[files("./tests/cases_graph/*")] path: PathBuf,
+    #[values("dot", "json")] format: &str,
+) {
+    let output_path = std::env::temp_dir()
+        .join("wgso_graph")
+        .join(path.file_name().unwrap())
+        .with_extension(format);
+    fs::create_dir_all(output_path.parent().unwrap()).unwrap();
+    Args::parse_from([
+        "wgso",
+        "graph",
+        path.to_str().unwrap(),
+        "--format",
+        format,
+        "--output",
+        output_path.to_str().unwrap(),
+    ])
+    .run();
+    let actual = fs::read_to_string(output_path).unwrap();
+    let expected_path = path.join(format!(".expected.{format}"));
+    if expected_path.exists() {
+        assert_eq!(
+            fs::read_to_string(expected_path).unwrap(),
+            actual,
+            "mismatching {format} graph for {:?} case",
+            path.file_stem().unwrap(),
+        );
+    } else {
+        fs::write(expected_path, actual).unwrap();
+        panic!("expected graph saved on disk, please check and rerun the tests");
+    }
+}
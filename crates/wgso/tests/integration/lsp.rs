@@ -0,0 +1,232 @@
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::notification::{Exit, Initialized, Notification as _, PublishDiagnostics};
+use lsp_types::request::{
+    Completion, GotoDefinition, HoverRequest, Initialize, Request as _, Shutdown,
+};
+use lsp_types::{
+    CompletionResponse, GotoDefinitionResponse, Hover, HoverContents, InitializeParams, Position,
+    PublishDiagnosticsParams, TextDocumentIdentifier, Url, WorkspaceFolder,
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use wgso::LanguageServer;
+
+#[test]
+fn publish_diagnostics_for_valid_program() {
+    let client = Client::start("tests/cases_valid/imports");
+    let diagnostics = client.diagnostics(3);
+    assert!(diagnostics
+        .iter()
+        .all(|params| params.diagnostics.is_empty()));
+    client.stop();
+}
+
+#[test]
+fn publish_diagnostics_for_invalid_program() {
+    let client = Client::start("tests/cases_invalid/directive_pre_validation");
+    let diagnostics = client.diagnostics(3);
+    let import_diagnostics = diagnostics
+        .iter()
+        .find(|params| params.uri.path().ends_with("import.wgsl"))
+        .unwrap();
+    assert_eq!(import_diagnostics.diagnostics.len(), 2);
+    assert_eq!(
+        import_diagnostics.diagnostics[1].message,
+        format!(
+            "'{}/cases_invalid/directive_pre_validation/missing.wgsl' file does not exist",
+            Client::absolute("tests")
+        )
+    );
+    assert_eq!(
+        import_diagnostics.diagnostics[1].range.start,
+        Position::new(2, 8)
+    );
+    client.stop();
+}
+
+#[test]
+fn find_definition() {
+    let mut client = Client::start("tests/cases_valid/imports");
+    client.diagnostics(3);
+    let response: GotoDefinitionResponse = client.request(
+        GotoDefinition::METHOD,
+        "tests/cases_valid/imports/root.wgsl",
+        Position::new(6, 25),
+    );
+    let GotoDefinitionResponse::Scalar(location) = response else {
+        panic!("invalid definition response");
+    };
+    assert!(location.uri.path().ends_with("imports/inner/inner.wgsl"));
+    assert_eq!(location.range.start, Position::new(0, 5));
+    assert_eq!(location.range.end, Position::new(0, 13));
+    let response: GotoDefinitionResponse = client.request(
+        GotoDefinition::METHOD,
+        "tests/cases_valid/imports/root.wgsl",
+        Position::new(1, 8),
+    );
+    let GotoDefinitionResponse::Scalar(location) = response else {
+        panic!("invalid definition response");
+    };
+    assert!(location.uri.path().ends_with("imports/root.wgsl"));
+    assert_eq!(location.range.start, Position::new(5, 17));
+    client.stop();
+}
+
+#[test]
+fn find_no_definition() {
+    let mut client = Client::start("tests/cases_valid/imports");
+    client.diagnostics(3);
+    let response: Value = client.request(
+        GotoDefinition::METHOD,
+        "tests/cases_valid/imports/root.wgsl",
+        Position::new(3, 2),
+    );
+    assert_eq!(response, Value::Null);
+    client.stop();
+}
+
+#[test]
+fn hover_storage_field() {
+    let mut client = Client::start("tests/cases_valid/shaders");
+    client.diagnostics(1);
+    let response: Hover = client.request(
+        HoverRequest::METHOD,
+        "tests/cases_valid/shaders/main.wgsl",
+        Position::new(6, 36),
+    );
+    let HoverContents::Markup(content) = response.contents else {
+        panic!("invalid hover response");
+    };
+    assert_eq!(
+        content.value,
+        "```wgsl\nmodes.inner: Modes\n```\nsize: 8 bytes, offset: 256 bytes"
+    );
+    client.stop();
+}
+
+#[test]
+fn complete_storage_fields() {
+    let mut client = Client::start("tests/cases_valid/shaders");
+    client.diagnostics(1);
+    let response: CompletionResponse = client.request(
+        Completion::METHOD,
+        "tests/cases_valid/shaders/main.wgsl",
+        Position::new(6, 34),
+    );
+    let CompletionResponse::Array(items) = response else {
+        panic!("invalid completion response");
+    };
+    let mut labels: Vec<_> = items.iter().map(|item| item.label.as_str()).collect();
+    labels.sort_unstable();
+    assert_eq!(labels, ["alignment", "inner"]);
+    let response: CompletionResponse = client.request(
+        Completion::METHOD,
+        "tests/cases_valid/shaders/main.wgsl",
+        Position::new(6, 30),
+    );
+    let CompletionResponse::Array(items) = response else {
+        panic!("invalid completion response");
+    };
+    assert_eq!(items.len(), 7);
+    client.stop();
+}
+
+struct Client {
+    connection: Connection,
+    server: thread::JoinHandle<()>,
+    next_id: i32,
+}
+
+impl Client {
+    #[allow(deprecated)]
+    fn start(path: &str) -> Self {
+        let (server_connection, connection) = Connection::memory();
+        let server = thread::spawn(|| {
+            LanguageServer::new(server_connection, ".").run().unwrap();
+        });
+        let uri = Url::from_file_path(fs::canonicalize(path).unwrap()).unwrap();
+        let mut client = Self {
+            connection,
+            server,
+            next_id: 0,
+        };
+        let params = InitializeParams {
+            workspace_folders: Some(vec![WorkspaceFolder {
+                uri,
+                name: "program".into(),
+            }]),
+            ..InitializeParams::default()
+        };
+        client.send_request(Initialize::METHOD, serde_json::to_value(params).unwrap());
+        client.receive_response();
+        client.send_notification(Initialized::METHOD, json!({}));
+        client
+    }
+
+    fn stop(mut self) {
+        self.send_request(Shutdown::METHOD, Value::Null);
+        self.receive_response();
+        self.send_notification(Exit::METHOD, Value::Null);
+        self.server.join().unwrap();
+    }
+
+    fn diagnostics(&self, file_count: usize) -> Vec<PublishDiagnosticsParams> {
+        (0..file_count)
+            .map(|_| match self.connection.receiver.recv().unwrap() {
+                Message::Notification(notification) => {
+                    assert_eq!(notification.method, PublishDiagnostics::METHOD);
+                    serde_json::from_value(notification.params).unwrap()
+                }
+                message @ (Message::Request(_) | Message::Response(_)) => {
+                    panic!("unexpected message: {message:?}")
+                }
+            })
+            .collect()
+    }
+
+    fn request<T: DeserializeOwned>(&mut self, method: &str, path: &str, position: Position) -> T {
+        let uri = Url::from_file_path(fs::canonicalize(path).unwrap()).unwrap();
+        let params = json!({
+            "textDocument": TextDocumentIdentifier { uri },
+            "position": position,
+        });
+        self.send_request(method, params);
+        serde_json::from_value(self.receive_response()).unwrap()
+    }
+
+    fn send_request(&mut self, method: &str, params: Value) {
+        self.next_id += 1;
+        let request = Request::new(RequestId::from(self.next_id), method.into(), params);
+        self.connection
+            .sender
+            .send(Message::Request(request))
+            .unwrap();
+    }
+
+    fn send_notification(&self, method: &str, params: Value) {
+        let notification = Notification::new(method.into(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+    }
+
+    fn receive_response(&self) -> Value {
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) => return response.result.unwrap_or(Value::Null),
+                Message::Notification(_) | Message::Request(_) => (),
+            }
+        }
+    }
+
+    fn absolute(path: &str) -> String {
+        fs::canonicalize(Path::new(path))
+            .unwrap()
+            .display()
+            .to_string()
+    }
+}
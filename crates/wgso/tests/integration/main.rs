@@ -1,8 +1,16 @@
 #![allow(missing_docs, clippy::unwrap_used)]
 
+mod config;
+mod doc;
 mod draw;
+mod embedding;
 mod examples;
+mod gamepad;
+mod graph;
 mod invalid_cases;
+mod lsp;
+mod plan;
+mod reflection;
 mod runner;
 mod toggle;
 mod valid_cases;
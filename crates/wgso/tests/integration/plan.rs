@@ -0,0 +1,71 @@
+use std::path::Path;
+use wgso::{ExecutionKind, Program};
+
+#[test]
+fn retrieve_execution_plan() {
+    let program = Program::parse(Path::new("tests/cases_valid/shaders"));
+    let plan = program.execution_plan().unwrap();
+    let kinds: Vec<_> = plan
+        .compute_steps
+        .iter()
+        .chain(&plan.render_steps)
+        .map(|step| (step.kind, step.priority))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            (ExecutionKind::Init, 0),
+            (ExecutionKind::Init, 0),
+            (ExecutionKind::Run, 42),
+            (ExecutionKind::Run, -42),
+            (ExecutionKind::Draw, 42),
+            (ExecutionKind::Draw, -42),
+        ]
+    );
+    assert_eq!(plan.compute_steps[1].shader, "main.compute");
+    assert_eq!(plan.compute_steps[1].workgroup_count, Some((1, 1, 1)));
+    assert_eq!(
+        plan.compute_steps[1].args,
+        [("mode".into(), "modes.inner.mode0".into())]
+    );
+    assert_eq!(plan.render_steps[0].workgroup_count, None);
+    assert_eq!(
+        plan.render_steps[0].instance_buffer.as_deref(),
+        Some("instance2")
+    );
+}
+
+#[test]
+fn display_execution_plan() {
+    let program = Program::parse(Path::new("tests/cases_graph/scenes"));
+    assert_eq!(
+        program.execution_plan().unwrap().to_string(),
+        "\
+Compute pass:
+  1. #init main.init (priority: 0, workgroups: 1x1x1)
+     called from: main.main
+     storages: speed
+  2. #run scenes.running.update (priority: 10, workgroups: 1x1x1)
+     called from: scenes.running.main
+     args: step=speed
+     storages: instances, is_paused
+  3. #run scenes.paused.blink (priority: -5, workgroups: 1x1x1)
+     called from: scenes.paused.main
+     storages: instances
+     toggles: is_paused
+Render pass:
+  4. #draw main.render (priority: 0)
+     called from: main.main
+     vertex buffer: vertices
+     instance buffer: instances
+"
+    );
+}
+
+#[test]
+fn retrieve_execution_plan_of_invalid_program() {
+    let program = Program::parse(Path::new("tests/cases_invalid/directive_pre_validation"));
+    let errors = program.execution_plan().unwrap_err();
+    assert_eq!(errors.len(), program.errors.len());
+    assert!(!errors.is_empty());
+}
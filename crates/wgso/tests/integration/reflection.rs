@@ -0,0 +1,122 @@
+use std::path::Path;
+use wgso::{BindingInfoKind, ExecutionKind, Program, Runner, SectionKind, ShaderKind};
+
+#[test]
+fn retrieve_sections() {
+    let program = Program::parse(Path::new("tests/cases_graph/scenes"));
+    let sections = program.sections();
+    let ids: Vec<_> = sections
+        .iter()
+        .map(|section| (section.id.as_str(), section.kind))
+        .collect();
+    assert_eq!(
+        ids,
+        [
+            ("main.init", SectionKind::ComputeShader),
+            ("main.main", SectionKind::Mod),
+            ("main.render", SectionKind::RenderShader),
+            ("scenes.paused.blink", SectionKind::ComputeShader),
+            ("scenes.paused.main", SectionKind::Mod),
+            ("scenes.running.main", SectionKind::Mod),
+            ("scenes.running.update", SectionKind::ComputeShader),
+        ]
+    );
+    assert_eq!(sections[0].imports, ["main.main"]);
+    assert_eq!(sections[0].directive.code, "# shader < compute > init");
+}
+
+#[test]
+fn retrieve_storages() {
+    let program = Program::parse(Path::new("tests/cases_valid/shaders"));
+    let storages = program.storages();
+    let modes = storages
+        .iter()
+        .find(|storage| storage.name == "modes")
+        .unwrap();
+    assert_eq!(modes.type_.label, "ModeContainer");
+    assert_eq!(modes.type_.size, 264);
+    assert_eq!(modes.sections, ["main.init", "main.main"]);
+    assert!(!modes.is_toggleable);
+    let alignment = &modes.type_.fields[0];
+    assert_eq!(alignment.name, "alignment");
+    let (item_type, length) = alignment.type_.array.as_ref().unwrap();
+    assert_eq!((item_type.label.as_str(), *length), ("u32", 64));
+    let inner = &modes.type_.fields[1];
+    assert_eq!((inner.type_.offset, inner.type_.size), (256, 8));
+    assert_eq!(inner.type_.fields[1].type_.offset, 260);
+}
+
+#[test]
+fn retrieve_shaders() {
+    let program = Program::parse(Path::new("tests/cases_graph/scenes"));
+    let shaders = program.shaders();
+    let update = shaders
+        .iter()
+        .find(|shader| shader.id == "scenes.running.update")
+        .unwrap();
+    assert_eq!(update.kind, ShaderKind::Compute);
+    assert_eq!(update.workgroup_count, Some((1, 1, 1)));
+    assert_eq!(update.vertex_type, None);
+    let step = update.bindings.last().unwrap();
+    assert_eq!(step.name, "step");
+    assert_eq!(step.kind, BindingInfoKind::Uniform);
+    assert_eq!(step.index, 4);
+    assert!(step.is_read_only);
+    assert!(update.code.contains("fn main()"));
+    let render = shaders
+        .iter()
+        .find(|shader| shader.id == "main.render")
+        .unwrap();
+    assert_eq!(render.kind, ShaderKind::Render);
+    assert_eq!(render.workgroup_count, None);
+    assert_eq!(render.vertex_type.as_deref(), Some("Vertex"));
+    assert_eq!(render.instance_type.as_deref(), Some("Instance"));
+}
+
+#[test]
+fn retrieve_toggles_and_call_sites() {
+    let program = Program::parse(Path::new("tests/cases_graph/scenes"));
+    let toggles = program.toggles();
+    assert_eq!(toggles.len(), 1);
+    assert_eq!(toggles[0].buffer, "is_paused");
+    assert_eq!(toggles[0].prefix, "scenes.paused");
+    assert_eq!(
+        toggles[0].sections,
+        ["scenes.paused.blink", "scenes.paused.main"]
+    );
+    let calls: Vec<_> = program
+        .call_sites()
+        .into_iter()
+        .map(|call| (call.kind, call.shader, call.caller))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            (ExecutionKind::Init, "main.init".into(), "main.main".into()),
+            (
+                ExecutionKind::Draw,
+                "main.render".into(),
+                "main.main".into()
+            ),
+            (
+                ExecutionKind::Run,
+                "scenes.paused.blink".into(),
+                "scenes.paused.main".into()
+            ),
+            (
+                ExecutionKind::Run,
+                "scenes.running.update".into(),
+                "scenes.running.main".into()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn retrieve_program_from_runner() {
+    let path = Path::new("tests/cases_valid/shaders");
+    let runner = Runner::new(path, None, Some((10, 8))).unwrap();
+    let program = Program::parse(path);
+    assert_eq!(runner.program().storages(), program.storages());
+    assert_eq!(runner.program().shaders(), program.shaders());
+}
@@ -1,5 +1,7 @@
+use std::cell::RefCell;
 use std::path::Path;
-use wgso::{Error, Runner};
+use std::rc::Rc;
+use wgso::{AdapterOptions, Error, Event, Runner, StateProvider};
 
 #[test]
 fn run_invalid_directory_path() {
@@ -12,6 +14,36 @@ fn run_invalid_directory_path() {
         .contains("invalid_path: No such file or directory"));
 }
 
+#[test]
+fn list_adapters() {
+    assert!(!wgso::adapters(&AdapterOptions::default()).is_empty());
+}
+
+#[test]
+fn run_with_adapter_index() {
+    let options = AdapterOptions {
+        adapter: Some("0".into()),
+        ..AdapterOptions::default()
+    };
+    let mut runner =
+        Runner::with_adapter(Path::new("tests/cases_valid/shaders"), None, None, &options).unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read("modes.inner.mode1"), vec![1, 0, 0, 0]);
+}
+
+#[test]
+fn run_with_not_existing_adapter() {
+    let options = AdapterOptions {
+        adapter: Some("not existing adapter".into()),
+        ..AdapterOptions::default()
+    };
+    let program =
+        Runner::with_adapter(Path::new("tests/cases_valid/shaders"), None, None, &options)
+            .expect_err("not existing adapter has not returned error");
+    assert_eq!(program.errors.len(), 1);
+    assert!(matches!(program.errors[0], Error::AdapterNotFound(_)));
+}
+
 #[test]
 fn retrieve_not_existing_buffer() {
     let runner = Runner::new(Path::new("tests/cases_valid/storages"), None, None).unwrap();
@@ -47,3 +79,148 @@ fn retrieve_delta_time() {
     runner.run_step().unwrap();
     assert!(runner.delta_secs() > 0.);
 }
+
+#[test]
+fn run_with_state_providers() {
+    let mut runner = Runner::new(Path::new("tests/case_state_provider"), None, None).unwrap();
+    let totals = Rc::new(RefCell::new(vec![]));
+    runner.add_state_provider(NetworkProvider { value: 0 });
+    runner.add_state_provider(TotalProvider {
+        totals: totals.clone(),
+    });
+    runner.run_step().unwrap();
+    runner.run_step().unwrap();
+    runner.run_step().unwrap();
+    assert_eq!(runner.read("total"), vec![12, 0, 0, 0]);
+    assert_eq!(*totals.borrow(), vec![0, 2, 6]);
+}
+
+#[test]
+fn run_with_state_provider_with_unknown_storage() {
+    let mut runner = Runner::new(Path::new("tests/case_state_provider"), None, None).unwrap();
+    runner.add_state_provider(InvalidProvider {
+        path: "network.invalid",
+        data: vec![],
+    });
+    runner.run_step().unwrap_err();
+    let program = runner.run_step().unwrap_err();
+    assert_eq!(program.errors.len(), 1);
+    assert!(matches!(program.errors[0], Error::InvalidStateProvider(_)));
+    assert!(program.errors[0]
+        .render(program)
+        .contains("storage `network.invalid` of state provider not found"));
+}
+
+#[test]
+fn run_with_state_provider_with_invalid_size() {
+    let mut runner = Runner::new(Path::new("tests/case_state_provider"), None, None).unwrap();
+    runner.add_state_provider(InvalidProvider {
+        path: "network",
+        data: vec![0; 4],
+    });
+    runner.run_step().unwrap_err();
+    let program = runner.run_step().unwrap_err();
+    assert_eq!(program.errors.len(), 1);
+    assert!(program.errors[0]
+        .render(program)
+        .contains("state provider has returned 4 bytes for storage `network` of 8 bytes"));
+}
+
+#[test]
+fn drain_events() {
+    let path = Path::new("tests/case_event");
+    wgso_deps::retrieve_dependencies(path.join("wgso.yaml")).unwrap();
+    let mut runner = Runner::new(path, None, None).unwrap();
+    let frame_indexes = Rc::new(RefCell::new(vec![]));
+    let handled_frame_indexes = frame_indexes.clone();
+    runner.add_event_handler(Event::CUSTOM + 1, move |event| {
+        handled_frame_indexes.borrow_mut().push(event.params[0]);
+    });
+    assert!(runner.drain_events().is_empty());
+    runner.run_step().unwrap();
+    let events = runner.drain_events();
+    assert_eq!(
+        events,
+        [
+            Event {
+                kind: Event::EXIT,
+                params: [1, 2, 3, 4],
+            },
+            Event {
+                kind: Event::CUSTOM + 1,
+                params: [1, 0, 0, 0],
+            },
+        ]
+    );
+    assert!(runner.drain_events().is_empty());
+    runner.run_step().unwrap();
+    assert_eq!(runner.drain_events().len(), 2);
+    assert_eq!(*frame_indexes.borrow(), [1, 2]);
+}
+
+#[test]
+fn drain_events_without_std() {
+    let mut runner = Runner::new(Path::new("tests/case_state_provider"), None, None).unwrap();
+    runner.run_step().unwrap();
+    assert!(runner.drain_events().is_empty());
+}
+
+#[derive(Debug)]
+struct NetworkProvider {
+    value: u32,
+}
+
+impl StateProvider for NetworkProvider {
+    fn path(&self) -> &'static str {
+        "network"
+    }
+
+    fn data(&mut self) -> Option<Vec<u8>> {
+        self.value += 1;
+        Some(
+            [self.value, 2]
+                .into_iter()
+                .flat_map(u32::to_ne_bytes)
+                .collect(),
+        )
+    }
+}
+
+#[derive(Debug)]
+struct TotalProvider {
+    totals: Rc<RefCell<Vec<u8>>>,
+}
+
+impl StateProvider for TotalProvider {
+    fn path(&self) -> &'static str {
+        "total"
+    }
+
+    fn data(&mut self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn is_read_back(&self) -> bool {
+        true
+    }
+
+    fn read_back(&mut self, data: &[u8]) {
+        self.totals.borrow_mut().push(data[0]);
+    }
+}
+
+#[derive(Debug)]
+struct InvalidProvider {
+    path: &'static str,
+    data: Vec<u8>,
+}
+
+impl StateProvider for InvalidProvider {
+    fn path(&self) -> &str {
+        self.path
+    }
+
+    fn data(&mut self) -> Option<Vec<u8>> {
+        Some(self.data.clone())
+    }
+}
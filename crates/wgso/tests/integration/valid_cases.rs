@@ -31,9 +31,10 @@ fn run_valid_code(#[files("./tests/cases_valid/*")] path: PathBuf) {
         .map(|buffer| {
             if buffer == "std_" {
                 format!(
-                    "std_.time.frame_index={:?}\nstd_.surface={:?}",
+                    "std_.time.frame_index={:?}\nstd_.surface={:?}\nstd_.lifecycle={:?}",
                     runner.read("std_.time.frame_index"),
                     runner.read("std_.surface"),
+                    runner.read("std_.lifecycle"),
                 )
             } else {
                 format!("{buffer}={:?}", runner.read(buffer))
@@ -0,0 +1,43 @@
+use crate::Error;
+use flate2::read::GzDecoder;
+use liblzma::read::XzDecoder;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = b"\x1f\x8b";
+const XZ_MAGIC: &[u8] = b"\xfd7zXZ\x00";
+
+/// Extracts an archive in `extracted_path`.
+///
+/// The archive format is detected from the file content.
+/// `url` is only used to describe the archive in errors.
+pub(crate) fn extract(archive_path: &Path, extracted_path: &Path, url: &str) -> Result<(), Error> {
+    let mut file = File::open(archive_path).map_err(|e| Error::Io(archive_path.into(), e))?;
+    let mut magic = [0; 6];
+    let magic_len = file
+        .read(&mut magic)
+        .map_err(|e| Error::Io(archive_path.into(), e))?;
+    file.rewind()
+        .map_err(|e| Error::Io(archive_path.into(), e))?;
+    let magic = &magic[..magic_len];
+    if magic.starts_with(ZIP_MAGIC) {
+        zip::ZipArchive::new(file)
+            .map_err(Error::Zip)?
+            .extract(extracted_path)
+            .map_err(Error::Zip)
+    } else if magic.starts_with(GZIP_MAGIC) {
+        extract_tar(GzDecoder::new(file), archive_path, extracted_path)
+    } else if magic.starts_with(XZ_MAGIC) {
+        extract_tar(XzDecoder::new(file), archive_path, extracted_path)
+    } else {
+        Err(Error::UnsupportedArchive(url.into()))
+    }
+}
+
+fn extract_tar(reader: impl Read, archive_path: &Path, extracted_path: &Path) -> Result<(), Error> {
+    tar::Archive::new(reader)
+        .unpack(extracted_path)
+        .map_err(|e| Error::Io(archive_path.into(), e))
+}
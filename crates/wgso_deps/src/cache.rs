@@ -0,0 +1,172 @@
+use crate::source::{Retrieval, Source};
+use crate::{hash, source, Error};
+use std::env;
+use std::fs;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+const CACHE_FOLDER_ENV_VAR: &str = "WGSO_CACHE_DIR";
+const FILES_FOLDER_NAME: &str = "files";
+const RETRIEVAL_FILE_NAME: &str = "retrieval.yaml";
+const REGISTRY_INDEX_FILE_NAME: &str = "index.yaml";
+
+/// A user-level cache of remote dependencies shared between programs.
+///
+/// Each cached dependency is stored in a `<source key>/<content hash>` folder,
+/// where the source key is a hash of the dependency source.
+/// Indexes of remote registries are stored in a `<registry key>/index.yaml` file.
+#[derive(Debug)]
+pub(crate) struct Cache {
+    folder_path: Option<PathBuf>,
+}
+
+impl Cache {
+    pub(crate) fn new(folder_path: Option<&Path>) -> Self {
+        Self {
+            folder_path: folder_path
+                .map(Path::to_path_buf)
+                .or_else(default_folder_path),
+        }
+    }
+
+    /// Copies cached files of a dependency in `target_path`.
+    ///
+    /// If `hash` is defined, only the cached files with this hash are retrieved.
+    /// Otherwise, the most recently cached files are retrieved.
+    ///
+    /// `None` is returned if the dependency is not cached.
+    pub(crate) fn load(
+        &self,
+        source: &Source,
+        hash: Option<&str>,
+        target_path: &Path,
+    ) -> Result<Option<Retrieval>, Error> {
+        let Some(source_path) = self.source_path(source) else {
+            return Ok(None);
+        };
+        let entry_path = if let Some(hash) = hash {
+            Some(source_path.join(entry_name(hash)))
+        } else {
+            latest_entry_path(&source_path)
+        };
+        let Some(entry_path) = entry_path.filter(|path| path.is_dir()) else {
+            return Ok(None);
+        };
+        let retrieval_path = entry_path.join(RETRIEVAL_FILE_NAME);
+        let retrieval_file =
+            File::open(&retrieval_path).map_err(|e| Error::Io(retrieval_path.clone(), e))?;
+        let retrieval = serde_yml::from_reader(retrieval_file).map_err(Error::Deserialization)?;
+        source::copy_local_dependency(target_path, &entry_path.join(FILES_FOLDER_NAME))?;
+        Ok(Some(retrieval))
+    }
+
+    /// Stores retrieved files of a dependency located in `target_path`.
+    pub(crate) fn store(
+        &self,
+        source: &Source,
+        hash: &str,
+        retrieval: &Retrieval,
+        target_path: &Path,
+    ) -> Result<(), Error> {
+        let Some(source_path) = self.source_path(source) else {
+            return Ok(());
+        };
+        let entry_path = source_path.join(entry_name(hash));
+        if entry_path.is_dir() {
+            return Ok(());
+        }
+        fs::create_dir_all(&source_path).map_err(|e| Error::Io(source_path.clone(), e))?;
+        let tmp_folder =
+            tempfile::TempDir::new_in(&source_path).map_err(|e| Error::Io(source_path, e))?;
+        source::copy_local_dependency(&tmp_folder.path().join(FILES_FOLDER_NAME), target_path)?;
+        let retrieval_path = tmp_folder.path().join(RETRIEVAL_FILE_NAME);
+        let retrieval = serde_yml::to_string(retrieval).map_err(Error::Serialization)?;
+        fs::write(&retrieval_path, retrieval).map_err(|e| Error::Io(retrieval_path, e))?;
+        // another process may have stored the same files in the meantime
+        if fs::rename(tmp_folder.path(), &entry_path).is_ok() {
+            let _ = tmp_folder.keep();
+        }
+        Ok(())
+    }
+
+    /// Returns the cached content of the index of the registry located at `location`.
+    ///
+    /// `None` is returned if the index is not cached.
+    pub(crate) fn load_index(&self, location: &str) -> Result<Option<String>, Error> {
+        let Some(index_path) = self.index_path(location) else {
+            return Ok(None);
+        };
+        if !index_path.is_file() {
+            return Ok(None);
+        }
+        fs::read_to_string(&index_path)
+            .map(Some)
+            .map_err(|e| Error::Io(index_path, e))
+    }
+
+    /// Stores the content of the index of the registry located at `location`.
+    pub(crate) fn store_index(&self, location: &str, content: &str) -> Result<(), Error> {
+        let Some(index_path) = self.index_path(location) else {
+            return Ok(());
+        };
+        let folder_path = index_path
+            .parent()
+            .expect("internal error: cached index should be in a folder");
+        fs::create_dir_all(folder_path).map_err(|e| Error::Io(folder_path.into(), e))?;
+        let mut tmp_file = tempfile::NamedTempFile::new_in(folder_path)
+            .map_err(|e| Error::Io(folder_path.into(), e))?;
+        tmp_file
+            .write_all(content.as_bytes())
+            .map_err(|e| Error::Io(tmp_file.path().into(), e))?;
+        tmp_file
+            .persist(&index_path)
+            .map_err(|e| Error::Io(index_path, e.error))?;
+        Ok(())
+    }
+
+    fn source_path(&self, source: &Source) -> Option<PathBuf> {
+        let folder_path = self.folder_path.as_ref()?;
+        let key = source.cache_key()?;
+        Some(folder_path.join(hash::hash_bytes(key.as_bytes())))
+    }
+
+    fn index_path(&self, location: &str) -> Option<PathBuf> {
+        let folder_path = self.folder_path.as_ref()?;
+        let key = format!("registry\n{location}");
+        Some(
+            folder_path
+                .join(hash::hash_bytes(key.as_bytes()))
+                .join(REGISTRY_INDEX_FILE_NAME),
+        )
+    }
+}
+
+fn default_folder_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CACHE_FOLDER_ENV_VAR) {
+        Some(path.into())
+    } else if cfg!(target_os = "windows") {
+        env::var_os("LOCALAPPDATA").map(|path| PathBuf::from(path).join("wgso").join("cache"))
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|path| PathBuf::from(path).join("Library/Caches/wgso"))
+    } else if let Some(path) = env::var_os("XDG_CACHE_HOME") {
+        Some(PathBuf::from(path).join("wgso"))
+    } else {
+        env::var_os("HOME").map(|path| PathBuf::from(path).join(".cache/wgso"))
+    }
+}
+
+fn entry_name(hash: &str) -> String {
+    hash.replace(':', "-")
+}
+
+fn latest_entry_path(source_path: &Path) -> Option<PathBuf> {
+    source_path
+        .read_dir()
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().join(RETRIEVAL_FILE_NAME).is_file())
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
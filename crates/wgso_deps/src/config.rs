@@ -12,11 +12,28 @@ pub(crate) fn load(file_path: impl AsRef<Path>) -> Result<Config, Error> {
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Config {
+    #[serde(default)]
+    pub(crate) program: ProgramConfig,
+    #[serde(default)]
     pub(crate) dependencies: HashMap<String, DependencyConfig>,
 }
 
+// Other program properties are validated by `wgso`.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ProgramConfig {
+    pub(crate) version: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) struct DependencyConfig {
     pub(crate) path: Option<PathBuf>,
     pub(crate) url: Option<String>,
+    pub(crate) git: Option<String>,
+    pub(crate) rev: Option<String>,
+    pub(crate) tag: Option<String>,
+    pub(crate) branch: Option<String>,
+    pub(crate) subdir: Option<String>,
+    pub(crate) sha256: Option<String>,
+    pub(crate) registry: Option<String>,
+    pub(crate) version: Option<String>,
 }
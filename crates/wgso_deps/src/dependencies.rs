@@ -1,124 +1,208 @@
-use crate::{config, Error};
-use fs_extra::dir::CopyOptions;
-use std::path::Path;
+use crate::lock::{Lock, LOCK_FILE_NAME};
+use crate::resolver::Resolver;
+use crate::tree::DependencyTree;
+use crate::Error;
+use std::env;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
 
-const TARGET_FOLDER_NAME: &str = "_";
+pub(crate) const TARGET_FOLDER_NAME: &str = "_";
+pub(crate) const CONFIG_FILE_NAME: &str = "wgso.yaml";
+
+/// Retrieve dependency files based on a configuration file located at `config_path`.
+///
+/// This is equivalent to [`retrieve_dependencies_with_options`] with default options.
+///
+/// # Errors
+///
+/// See [`retrieve_dependencies_with_options`].
+pub fn retrieve_dependencies(config_path: impl AsRef<Path>) -> Result<(), Error> {
+    retrieve_dependencies_with_options(config_path, &RetrieveOptions::default())
+}
 
 /// Retrieve dependency files based on a configuration file located at `config_path`.
 ///
 /// Dependencies are put in a `_` folder next to the configuration file.
-/// If the dependency already exists, then the dependency is not retrieved again.
+/// If the dependency already exists, then the dependency is not retrieved again,
+/// except if an update is requested with `options`.
+///
+/// Dependencies of a dependency, configured in a `wgso.yaml` file at the root of the dependency
+/// folder, are also retrieved in the same `_` folder. A dependency declared in the configuration
+/// file located at `config_path` takes precedence over dependencies declared by other
+/// dependencies with the same name.
+///
+/// URL dependencies are recorded in a `wgso.lock` file next to the configuration file,
+/// with a hash of their files. Retrieved files are verified against this hash, so that
+/// the same dependency files are used in all environments.
+///
+/// Retrieved remote dependencies are stored in a user-level cache folder
+/// (see [`RetrieveOptions::cache_path`]), so that other programs can reuse them without
+/// downloading them again.
 ///
 /// # Configuration file format
 ///
 /// The YAML configuration file has the following format:
 /// ```yaml
+/// program:
+///   # optional version of the package, checked against requirements of dependent packages
+///   version: 1.2.0
 /// dependencies:
 ///   dependency1_name:
 ///     # path is relative to configuration file folder
 ///     # dependency is retrieved from local path '../deps/dependency1_name' (symbolic link is created)
 ///     path: ../deps/
 ///   dependency2_name:
-///     # dependency is located in '<first root folder>/dependency2_name' folder of ZIP file
-///     # accessible by URL
+///     # dependency is located in '<first root folder>/dependency2_name' folder of archive
+///     # accessible by URL (supported formats are ZIP, tar.gz and tar.xz)
 ///     url: https://github.com/orga/project/archive/refs/heads/main.zip
+///   dependency3_name:
+///     # dependency is located in 'project-1.0/libs/dependency3' folder of the archive
+///     # url can also be a `file://` URL or a path relative to configuration file folder
+///     url: ../mirror/project-1.0.tar.gz
+///     subdir: project-1.0/libs/dependency3
+///     # optional SHA-256 hash of the archive, verified before extraction
+///     sha256: 2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824
+///   dependency4_name:
+///     # dependency is located in 'dependency4_name' folder of Git repository
+///     # (or in folder specified by `subdir` key)
+///     git: https://github.com/orga/project.git
+///     # optional reference, exclusive with `branch` and `rev` (default branch by default)
+///     tag: v1.0.0
+///   dependency5_name:
+///     # dependency is retrieved from a registry (local path relative to configuration file folder,
+///     # or base URL), using the highest version matching the optional requirement
+///     registry: ../registry
+///     # optional semver requirement, also supported with other sources
+///     version: ^1.2
+/// ```
+///
+/// For Git dependencies, the retrieved commit is recorded in the lock file, and this commit is
+/// retrieved instead of the configured reference until the dependency is updated.
+/// In the same way, the version selected in a registry is kept until the dependency is updated.
+///
+/// # Registry format
+///
+/// A registry is a folder (or a base URL) containing an `index.yaml` file with the following
+/// format:
+/// ```yaml
+/// packages:
+///   dependency5_name:
+///     - version: 1.2.0
+///       # relative URLs are resolved from the registry location
+///       url: dependency5_name-1.2.0.tar.gz
+///       # optional, same meaning as in the configuration file
+///       subdir: dependency5_name-1.2.0/dependency5_name
+///       sha256: 2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824
 /// ```
 ///
 /// # Errors
 ///
-/// An error is returned if the configuration file is invalid or if there is an issue during
-/// dependency retrieval.
-pub fn retrieve_dependencies(config_path: impl AsRef<Path>) -> Result<(), Error> {
+/// An error is returned if the configuration file is invalid, if there is an issue during
+/// dependency retrieval, if dependency files don't match the hash of the lock file,
+/// if two dependencies require the same dependency from different sources,
+/// if a dependency version doesn't match a requirement, if no registry package matches a
+/// requirement, if dependencies have a cycle, or if a dependency to update with
+/// [`UpdatePolicy::Only`] is not a dependency of the program.
+pub fn retrieve_dependencies_with_options(
+    config_path: impl AsRef<Path>,
+    options: &RetrieveOptions,
+) -> Result<(), Error> {
     let config_path = config_path.as_ref();
     if !config_path.exists() || config_path.is_dir() {
         return Ok(());
     }
-    let config = config::load(config_path)?;
-    let config_folder_path = config_path
-        .parent()
-        .expect("internal error: config path should have a parent");
-    for (dep_name, dep_config) in config.dependencies {
-        let target_parent_path = config_folder_path.join(TARGET_FOLDER_NAME);
-        std::fs::create_dir_all(&target_parent_path)
-            .map_err(|e| Error::Io(target_parent_path.clone(), e))?;
-        let target_path = target_parent_path.join(&dep_name);
-        if target_path.exists() {
-            continue; // no-coverage (false negative)
-        }
-        let dep_path = dep_config
-            .path
-            .map(|path| config_folder_path.join(path))
-            .filter(|path| path.is_dir() || dep_config.url.is_none());
-        if let Some(dep_path) = dep_path {
-            link_local_dependency(&target_path, &dep_path, &dep_name)?;
-        } else if let Some(url) = dep_config.url {
-            retrieve_url_dependency(&target_path, &url, &dep_name)?;
-        } else {
-            return Err(Error::NoDependencySource(dep_name));
+    let config_folder_path = config_folder_path(config_path);
+    let lock_path = config_folder_path.join(LOCK_FILE_NAME);
+    let old_lock = Lock::load(&lock_path)?;
+    let mut resolver = Resolver::new(config_folder_path, options, &old_lock, None);
+    resolver.resolve_root(config_path)?;
+    resolver.check_cycles()?;
+    if let UpdatePolicy::Only(names) = &options.update {
+        if let Some(name) = names.iter().find(|name| !resolver.has_dependency(name)) {
+            return Err(Error::UnknownDependency(name.clone()));
         }
     }
+    resolver.remove_stale_dependencies()?;
+    if resolver.new_lock != old_lock
+        && (!resolver.new_lock.dependencies.is_empty() || lock_path.exists())
+    {
+        resolver.new_lock.save(&lock_path)?;
+    }
     Ok(())
 }
 
-fn link_local_dependency(target_path: &Path, dep_path: &Path, dep_name: &str) -> Result<(), Error> {
-    #[cfg(target_family = "unix")]
-    {
-        let source_path = std::env::current_dir()
-            .map_err(|e| Error::Io("<current folder>".into(), e))?
-            .join(dep_path.join(dep_name));
-        std::os::unix::fs::symlink(&source_path, target_path).map_err(|e| Error::Io(source_path, e))
-    }
-    #[cfg(target_family = "windows")]
-    {
-        let source_path = std::env::current_dir()
-            .map_err(|e| Error::Io("<current folder>".into(), e))?
-            .join(dep_path.join(dep_name));
-        std::os::windows::fs::symlink_dir(&source_path, target_path)
-            .map_err(|e| Error::Io(source_path, e))
-    }
-    #[cfg(not(any(target_family = "unix", target_family = "windows")))]
-    {
-        copy_local_dependency(target_path, dep_path, dep_name)
+/// Resolves the dependency tree of the configuration file located at `config_path`.
+///
+/// Dependencies are resolved like [`retrieve_dependencies_with_options`], but the program
+/// folder is not modified: the `_` folder and the `wgso.lock` file are only read, and
+/// dependencies that would need to be retrieved are retrieved in a temporary folder.
+///
+/// # Errors
+///
+/// See [`retrieve_dependencies_with_options`].
+pub fn resolve_dependencies(
+    config_path: impl AsRef<Path>,
+    options: &RetrieveOptions,
+) -> Result<DependencyTree, Error> {
+    let config_path = config_path.as_ref();
+    if !config_path.exists() || config_path.is_dir() {
+        return Ok(DependencyTree::default());
     }
+    let config_folder_path = config_folder_path(config_path);
+    let old_lock = Lock::load(&config_folder_path.join(LOCK_FILE_NAME))?;
+    let tmp_folder = TempDir::new().map_err(|e| Error::Io(env::temp_dir(), e))?;
+    let mut resolver = Resolver::new(
+        config_folder_path,
+        options,
+        &old_lock,
+        Some(tmp_folder.path()),
+    );
+    resolver.resolve_root(config_path)?;
+    resolver.check_cycles()?;
+    Ok(resolver.tree())
 }
 
-#[allow(dead_code)]
-fn copy_local_dependency(target_path: &Path, dep_path: &Path, dep_name: &str) -> Result<(), Error> {
-    let source_path = dep_path.join(dep_name);
-    fs_extra::copy_items(
-        &[&source_path],
-        target_path,
-        &CopyOptions::new().copy_inside(true),
-    )
-    .map(|_| ())
-    .map_err(|e| Error::Copy(source_path, target_path.into(), e))
+fn config_folder_path(config_path: &Path) -> &Path {
+    config_path
+        .parent()
+        .expect("internal error: config path should have a parent")
 }
 
-#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
-fn retrieve_url_dependency(target_path: &Path, url: &str, dep_name: &str) -> Result<(), Error> {
-    let tmp_folder = tempfile::TempDir::new().map_err(|e| Error::Io("temp folder".into(), e))?;
-    let zip_path = tmp_folder.path().join("files.zip");
-    let extracted_path = tmp_folder.path().join("files");
-    let mut response = reqwest::blocking::get(url).map_err(Error::Request)?;
-    let mut zip_file =
-        std::fs::File::create(&zip_path).map_err(|e| Error::Io(zip_path.clone(), e))?;
-    std::io::copy(&mut response, &mut zip_file).map_err(|e| Error::Io(zip_path.clone(), e))?;
-    zip::ZipArchive::new(
-        std::fs::File::open(&zip_path).map_err(|e| Error::Io(zip_path.clone(), e))?,
-    )
-    .map_err(Error::Zip)?
-    .extract(&extracted_path)
-    .map_err(Error::Zip)?;
-    let extracted_root_path = extracted_path
-        .read_dir()
-        .map_err(|e| Error::Io(zip_path.clone(), e))?
-        .filter_map(Result::ok)
-        .find(|entry| entry.path().is_dir())
-        .map_or(extracted_path, |entry| entry.path());
-    copy_local_dependency(target_path, &extracted_root_path, dep_name)
+/// Options of dependency retrieval.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetrieveOptions {
+    /// The dependencies to update.
+    pub update: UpdatePolicy,
+    /// Whether remote dependencies are only retrieved from the cache.
+    pub is_offline: bool,
+    /// The folder of the dependency cache.
+    ///
+    /// If `None`, the folder defined by the `WGSO_CACHE_DIR` environment variable is used,
+    /// or a default user-level cache folder if the variable is not defined.
+    pub cache_path: Option<PathBuf>,
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
-fn retrieve_url_dependency(_target_path: &Path, _url: &str, _dep_name: &str) -> Result<(), Error> {
-    panic!("`wgso_deps` crate is only supported on Window, Linux and macOS")
+/// The dependencies to update during retrieval.
+///
+/// Updated dependencies are retrieved again even if they already exist,
+/// and their entry in the lock file is replaced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum UpdatePolicy {
+    /// No dependency is updated, retrieved files must match the lock file.
+    #[default]
+    Locked,
+    /// All dependencies are updated.
+    All,
+    /// Only dependencies with the listed names are updated.
+    Only(Vec<String>),
+}
+
+impl UpdatePolicy {
+    pub(crate) fn is_updated(&self, dep_name: &str) -> bool {
+        match self {
+            Self::Locked => false,
+            Self::All => true,
+            Self::Only(names) => names.iter().any(|name| name == dep_name),
+        }
+    }
 }
@@ -22,7 +22,46 @@ pub enum Error {
     /// A deserialization error.
     #[error("invalid format: {0}")]
     Deserialization(serde_yml::Error),
-    /// A deserialization error.
+    /// A serialization error.
+    #[error("serialization error: {0}")]
+    Serialization(serde_yml::Error),
+    /// A Git command error.
+    #[error("Git error: {0}")]
+    Git(String),
+    /// A dependency with an invalid source configuration.
+    #[error("dependency '{0}' has an invalid source: {1}")]
+    InvalidSource(String, String),
+    /// A dependency without source.
     #[error("dependency '{0}' has no configured source")]
     NoDependencySource(String),
+    /// A dependency whose files don't match the hash of the lock file.
+    #[error("dependency '{0}' has hash {2} instead of locked hash {1}, an update is required")]
+    HashMismatch(String, String, String),
+    /// An archive whose format is not supported.
+    #[error("unsupported archive format for {0} (supported formats are ZIP, tar.gz and tar.xz)")]
+    UnsupportedArchive(String),
+    /// A dependency archive that doesn't match the configured SHA-256 hash.
+    #[error("archive of dependency '{0}' has SHA-256 hash {2} instead of configured hash {1}")]
+    ArchiveHashMismatch(String, String, String),
+    /// A remote dependency not found in cache in offline mode.
+    #[error("dependency '{0}' is not in cache, it cannot be retrieved in offline mode")]
+    NotCached(String),
+    /// An invalid version or version requirement.
+    #[error("dependency '{0}' has an invalid version: {1}")]
+    InvalidVersion(String, String),
+    /// A dependency whose version doesn't match the version requirement.
+    #[error("dependency '{0}' has version {2} that doesn't match requirement {1}")]
+    VersionMismatch(String, String, String),
+    /// A dependency not found in a registry.
+    #[error("no version of dependency '{0}' matches requirement {1} in registry {2}")]
+    PackageNotFound(String, String, String),
+    /// A dependency required from different sources.
+    #[error("dependency '{0}' is required from both {1} and {2}")]
+    Conflict(String, String, String),
+    /// A dependency cycle, where the first and last dependency names are the same.
+    #[error("dependency cycle detected: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    /// A dependency to update that is not a dependency of the program.
+    #[error("dependency '{0}' to update is not a dependency of the program")]
+    UnknownDependency(String),
 }
@@ -0,0 +1,78 @@
+use crate::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::process::Command;
+
+/// A Git reference to checkout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GitReference {
+    DefaultBranch,
+    Branch(String),
+    Tag(String),
+    Rev(String),
+}
+
+impl GitReference {
+    pub(crate) fn name(&self) -> Option<&str> {
+        match self {
+            Self::DefaultBranch => None,
+            Self::Branch(name) | Self::Tag(name) | Self::Rev(name) => Some(name),
+        }
+    }
+}
+
+impl Display for GitReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DefaultBranch => write!(f, "default branch"),
+            Self::Branch(branch) => write!(f, "branch {branch}"),
+            Self::Tag(tag) => write!(f, "tag {tag}"),
+            Self::Rev(rev) => write!(f, "rev {rev}"),
+        }
+    }
+}
+
+/// Clones a Git repository in `clone_path` and returns the checked out commit.
+///
+/// If `revision` is defined, it is checked out instead of `reference`.
+pub(crate) fn clone(
+    repository: &str,
+    reference: &GitReference,
+    revision: Option<&str>,
+    clone_path: &Path,
+) -> Result<String, Error> {
+    let clone_path_str = clone_path.to_string_lossy();
+    // `--` prevents values coming from configuration files to be parsed as options
+    run(
+        &["clone", "--quiet", "--", repository, &clone_path_str],
+        None,
+    )?;
+    if let Some(checked_out) = revision.or_else(|| reference.name()) {
+        run(
+            &["checkout", "--quiet", checked_out, "--"],
+            Some(clone_path),
+        )?;
+    }
+    run(&["rev-parse", "HEAD"], Some(clone_path)).map(|commit| commit.trim().into())
+}
+
+fn run(args: &[&str], folder_path: Option<&Path>) -> Result<String, Error> {
+    let mut command = Command::new("git");
+    command.args(args);
+    if let Some(folder_path) = folder_path {
+        command.current_dir(folder_path);
+    }
+    let output = command
+        .output()
+        .map_err(|e| Error::Io("<git executable>".into(), e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(Error::Git(format!(
+            "`git {}` has failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
@@ -0,0 +1,72 @@
+use crate::Error;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+use walkdir::WalkDir;
+
+const HASH_PREFIX: &str = "sha256:";
+
+const EXCLUDED_FOLDER_NAME: &str = ".git";
+
+/// Computes a hash of the files contained in a folder.
+///
+/// The hash depends on the relative path and the content of each file,
+/// so it is the same for two identical folders located at different places.
+///
+/// The `.git` folder located at the root of the folder is ignored.
+pub(crate) fn hash_folder(folder_path: &Path) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+    for entry in WalkDir::new(folder_path)
+        .follow_links(true)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| !is_excluded(entry.path(), folder_path))
+    {
+        let entry = entry.map_err(|e| Error::Io(folder_path.into(), e.into()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative_path = entry
+            .path()
+            .strip_prefix(folder_path)
+            .expect("internal error: walked path should be in walked folder");
+        let content = fs::read(entry.path()).map_err(|e| Error::Io(entry.path().into(), e))?;
+        let normalized_path = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        hasher.update(normalized_path.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("{HASH_PREFIX}{}", to_hex(&hasher.finalize())))
+}
+
+/// Returns whether a path is ignored when a dependency folder is copied or hashed.
+pub(crate) fn is_excluded(path: &Path, folder_path: &Path) -> bool {
+    path.parent() == Some(folder_path) && path.file_name() == Some(EXCLUDED_FOLDER_NAME.as_ref())
+}
+
+/// Computes the SHA-256 hash of a file in hexadecimal format.
+pub(crate) fn hash_file(file_path: &Path) -> Result<String, Error> {
+    let mut hasher = Sha256::new();
+    let mut file = fs::File::open(file_path).map_err(|e| Error::Io(file_path.into(), e))?;
+    io::copy(&mut file, &mut hasher).map_err(|e| Error::Io(file_path.into(), e))?;
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// Computes the SHA-256 hash of bytes in hexadecimal format.
+pub(crate) fn hash_bytes(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
@@ -2,9 +2,20 @@
 //!
 //! See [`retrieve_dependencies`] to retrieve dependencies from a configuration file.
 
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+mod archive;
+mod cache;
 mod config;
 mod dependencies;
 mod errors;
+mod git;
+mod hash;
+mod lock;
+mod registry;
+mod resolver;
+mod source;
+mod tree;
 
 pub use dependencies::*;
 pub use errors::*;
+pub use tree::*;
@@ -0,0 +1,57 @@
+use crate::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::path::Path;
+
+pub(crate) const LOCK_FILE_NAME: &str = "wgso.lock";
+
+/// The content of a `wgso.lock` file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Lock {
+    pub(crate) dependencies: BTreeMap<String, LockedDependency>,
+}
+
+impl Lock {
+    pub(crate) fn load(file_path: &Path) -> Result<Self, Error> {
+        if !file_path.exists() {
+            return Ok(Self::default());
+        }
+        let file = File::open(file_path).map_err(|e| Error::Io(file_path.into(), e))?;
+        serde_yml::from_reader(file).map_err(Error::Deserialization)
+    }
+
+    pub(crate) fn save(&self, file_path: &Path) -> Result<(), Error> {
+        let content = serde_yml::to_string(self).map_err(Error::Serialization)?;
+        fs::write(file_path, content).map_err(|e| Error::Io(file_path.into(), e))
+    }
+}
+
+/// A dependency retrieved from a remote source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct LockedDependency {
+    /// The configured URL of the dependency.
+    pub(crate) url: String,
+    /// The configured Git reference of the dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) reference: Option<String>,
+    /// The configured folder of the dependency in the retrieved files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) subdir: Option<String>,
+    /// The configured SHA-256 hash of the retrieved archive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sha256: Option<String>,
+    /// The version of the retrieved package.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+    /// The URL from which files have been downloaded, after redirections.
+    pub(crate) resolved_url: String,
+    /// The retrieved Git commit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) revision: Option<String>,
+    /// The hash of the dependency files.
+    pub(crate) hash: String,
+    /// The retrieval time in seconds since Unix Epoch.
+    pub(crate) retrieved_at: u64,
+}
@@ -0,0 +1,118 @@
+use crate::cache::Cache;
+use crate::{source, Error};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const INDEX_FILE_NAME: &str = "index.yaml";
+
+/// The index of a registry, listing the available versions of each package.
+///
+/// The index is located in an `index.yaml` file at the root of the registry.
+#[derive(Debug, Deserialize)]
+pub(crate) struct RegistryIndex {
+    #[serde(default)]
+    packages: HashMap<String, Vec<RegistryPackage>>,
+    #[serde(skip)]
+    location: String,
+}
+
+impl RegistryIndex {
+    /// Loads the index of a registry located at `location`.
+    ///
+    /// The location can be an HTTP URL, a `file://` URL or a path relative to
+    /// `config_folder_path`.
+    ///
+    /// Indexes fetched from HTTP URLs are stored in the `cache`. In offline mode, they are loaded
+    /// from the `cache`, and `None` is returned if they are not cached.
+    pub(crate) fn load(
+        location: &str,
+        config_folder_path: &Path,
+        cache: &Cache,
+        is_offline: bool,
+    ) -> Result<Option<Self>, Error> {
+        let location = location.trim_end_matches('/');
+        let index_location = format!("{location}/{INDEX_FILE_NAME}");
+        let content = if let Some(index_path) =
+            source::local_file_path(&index_location, config_folder_path)
+        {
+            fs::read_to_string(&index_path).map_err(|e| Error::Io(index_path, e))?
+        } else if is_offline {
+            let Some(content) = cache.load_index(location)? else {
+                return Ok(None);
+            };
+            content
+        } else {
+            let content = fetch_index(&index_location)?;
+            cache.store_index(location, &content)?;
+            content
+        };
+        let mut index: Self = serde_yml::from_str(&content).map_err(Error::Deserialization)?;
+        index.location = location.into();
+        Ok(Some(index))
+    }
+
+    /// Returns the version and the package with the highest version matching `requirement`.
+    ///
+    /// If `preferred_version` matches `requirement` and is in the registry,
+    /// then this version is returned instead.
+    pub(crate) fn find(
+        &self,
+        dep_name: &str,
+        requirement: &VersionReq,
+        preferred_version: Option<&Version>,
+    ) -> Result<(Version, RegistryPackage), Error> {
+        let mut candidates = vec![];
+        for package in self.packages.get(dep_name).into_iter().flatten() {
+            let version = Version::parse(&package.version)
+                .map_err(|e| Error::InvalidVersion(dep_name.into(), e.to_string()))?;
+            if requirement.matches(&version) {
+                candidates.push((version, package));
+            }
+        }
+        let (version, package) = candidates
+            .iter()
+            .find(|(version, _)| Some(version) == preferred_version)
+            .or_else(|| candidates.iter().max_by(|(a, _), (b, _)| a.cmp(b)))
+            .ok_or_else(|| {
+                Error::PackageNotFound(
+                    dep_name.into(),
+                    requirement.to_string(),
+                    self.location.clone(),
+                )
+            })?;
+        let mut package = (*package).clone();
+        if !package.url.contains("://") {
+            package.url = format!("{}/{}", self.location, package.url);
+        }
+        Ok((version.clone(), package))
+    }
+}
+
+/// A version of a package listed in a registry index.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct RegistryPackage {
+    /// The package version.
+    pub(crate) version: String,
+    /// The URL of the package archive, relative to the registry location or absolute.
+    pub(crate) url: String,
+    /// The folder of the package in the archive.
+    pub(crate) subdir: Option<String>,
+    /// The SHA-256 hash of the archive.
+    pub(crate) sha256: Option<String>,
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn fetch_index(url: &str) -> Result<String, Error> {
+    reqwest::blocking::get(url)
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .and_then(reqwest::blocking::Response::text)
+        .map_err(Error::Request)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn fetch_index(_url: &str) -> Result<String, Error> {
+    panic!("`wgso_deps` crate is only supported on Window, Linux and macOS")
+}
@@ -0,0 +1,425 @@
+use crate::cache::Cache;
+use crate::config::Config;
+use crate::dependencies::{CONFIG_FILE_NAME, TARGET_FOLDER_NAME};
+use crate::lock::{Lock, LockedDependency};
+use crate::registry::{RegistryIndex, RegistryPackage};
+use crate::source::Source;
+use crate::tree::{DependencyTree, ResolvedDependency};
+use crate::{config, hash, source, Error, RetrieveOptions};
+use itertools::Itertools;
+use semver::{Version, VersionReq};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolves and retrieves dependencies recursively.
+#[derive(Debug)]
+pub(crate) struct Resolver<'a> {
+    folder_path: &'a Path,
+    options: &'a RetrieveOptions,
+    old_lock: &'a Lock,
+    tmp_folder_path: Option<&'a Path>,
+    pub(crate) new_lock: Lock,
+    cache: Cache,
+    registries: HashMap<String, RegistryIndex>,
+    requirements: HashMap<String, Requirement>,
+    versions: HashMap<String, Version>,
+    root_dep_names: Vec<String>,
+    dep_names: HashMap<String, Vec<String>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub(crate) fn new(
+        folder_path: &'a Path,
+        options: &'a RetrieveOptions,
+        old_lock: &'a Lock,
+        tmp_folder_path: Option<&'a Path>,
+    ) -> Self {
+        Self {
+            folder_path,
+            options,
+            old_lock,
+            tmp_folder_path,
+            new_lock: Lock::default(),
+            cache: Cache::new(options.cache_path.as_deref()),
+            registries: HashMap::new(),
+            requirements: HashMap::new(),
+            versions: HashMap::new(),
+            root_dep_names: vec![],
+            dep_names: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn resolve_root(&mut self, config_path: &Path) -> Result<(), Error> {
+        let config = config::load(config_path)?;
+        let config_folder_path = config_path
+            .parent()
+            .expect("internal error: config path should have a parent");
+        let requirements = self.load_requirements(config, config_folder_path)?;
+        for (dep_name, _) in &requirements {
+            self.root_dep_names.push(dep_name.clone());
+        }
+        self.requirements.extend(requirements);
+        for dep_name in self.root_dep_names.clone() {
+            self.resolve(&dep_name)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_cycles(&self) -> Result<(), Error> {
+        let mut checked_dep_names = HashSet::new();
+        for dep_name in self.dep_names.keys().sorted_unstable() {
+            self.check_cycle(dep_name, &mut vec![], &mut checked_dep_names)?;
+        }
+        Ok(())
+    }
+
+    /// Removes folders of the `_` folder that don't correspond to a resolved dependency.
+    pub(crate) fn remove_stale_dependencies(&self) -> Result<(), Error> {
+        let target_parent_path = self.folder_path.join(TARGET_FOLDER_NAME);
+        if !target_parent_path.is_dir() {
+            return Ok(());
+        }
+        let entries = target_parent_path
+            .read_dir()
+            .map_err(|e| Error::Io(target_parent_path.clone(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| Error::Io(target_parent_path.clone(), e))?;
+            let dep_name = entry.file_name().to_string_lossy().into_owned();
+            if !self.requirements.contains_key(&dep_name) {
+                let path = entry.path();
+                if path.is_file() {
+                    fs::remove_file(&path).map_err(|e| Error::Io(path, e))?;
+                } else {
+                    remove_dependency(&path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn has_dependency(&self, dep_name: &str) -> bool {
+        self.requirements.contains_key(dep_name)
+    }
+
+    pub(crate) fn tree(self) -> DependencyTree {
+        DependencyTree {
+            root_dependencies: self.root_dep_names,
+            dependencies: self
+                .requirements
+                .into_iter()
+                .map(|(dep_name, requirement)| {
+                    let dependency = ResolvedDependency {
+                        source: requirement.source.to_string(),
+                        version: self.versions.get(&dep_name).map(Version::to_string),
+                        dependencies: self.dep_names.get(&dep_name).cloned().unwrap_or_default(),
+                    };
+                    (dep_name, dependency)
+                })
+                .collect(),
+        }
+    }
+
+    fn load_requirements(
+        &mut self,
+        config: Config,
+        config_folder_path: &Path,
+    ) -> Result<Vec<(String, Requirement)>, Error> {
+        let mut requirements = vec![];
+        for (dep_name, dep_config) in config
+            .dependencies
+            .into_iter()
+            .sorted_unstable_by(|(name1, _), (name2, _)| name1.cmp(name2))
+        {
+            let version = dep_config
+                .version
+                .as_deref()
+                .map(VersionReq::parse)
+                .transpose()
+                .map_err(|e| Error::InvalidVersion(dep_name.clone(), e.to_string()))?;
+            let registry = dep_config
+                .registry
+                .as_deref()
+                .filter(|_| {
+                    source::local_path(&dep_name, &dep_config, config_folder_path).is_none()
+                })
+                .map(|location| registry_key(location, config_folder_path));
+            let (registry_version, package) =
+                if let (Some(location), Some(registry)) = (&dep_config.registry, &registry) {
+                    let requirement = version.clone().unwrap_or(VersionReq::STAR);
+                    let (registry_version, package) = self.find_package(
+                        &dep_name,
+                        (location, registry),
+                        &requirement,
+                        config_folder_path,
+                    )?;
+                    (Some(registry_version), Some(package))
+                } else {
+                    (None, None)
+                };
+            let source = Source::new(&dep_name, dep_config, config_folder_path, package)?;
+            let requirement = Requirement {
+                source,
+                version,
+                registry,
+                registry_version,
+            };
+            requirements.push((dep_name, requirement));
+        }
+        Ok(requirements)
+    }
+
+    fn find_package(
+        &mut self,
+        dep_name: &str,
+        (location, registry): (&str, &str),
+        requirement: &VersionReq,
+        config_folder_path: &Path,
+    ) -> Result<(Version, RegistryPackage), Error> {
+        if !self.registries.contains_key(registry) {
+            let index = RegistryIndex::load(
+                location,
+                config_folder_path,
+                &self.cache,
+                self.options.is_offline,
+            )?
+            .ok_or_else(|| Error::NotCached(dep_name.into()))?;
+            self.registries.insert(registry.into(), index);
+        }
+        let locked_version = self
+            .old_lock
+            .dependencies
+            .get(dep_name)
+            .filter(|_| !self.options.update.is_updated(dep_name))
+            .and_then(|locked| locked.version.as_deref())
+            .and_then(|version| Version::parse(version).ok());
+        self.registries[registry].find(dep_name, requirement, locked_version.as_ref())
+    }
+
+    fn resolve(&mut self, dep_name: &str) -> Result<(), Error> {
+        let target_path = self.retrieve(dep_name)?;
+        let config_path = target_path.join(CONFIG_FILE_NAME);
+        let config = if config_path.is_file() {
+            Some(config::load(&config_path)?)
+        } else {
+            None
+        };
+        let version = config
+            .as_ref()
+            .and_then(|config| config.program.version.as_deref())
+            .map(Version::parse)
+            .transpose()
+            .map_err(|e| Error::InvalidVersion(dep_name.into(), e.to_string()))?
+            .or_else(|| self.requirements[dep_name].registry_version.clone());
+        if let Some(requirement) = &self.requirements[dep_name].version {
+            check_version(dep_name, requirement, version.as_ref())?;
+        }
+        if let Some(version) = version {
+            if let Some(locked) = self.new_lock.dependencies.get_mut(dep_name) {
+                locked.version = Some(version.to_string());
+            }
+            self.versions.insert(dep_name.into(), version);
+        }
+        let requirements = if let Some(config) = config {
+            self.load_requirements(config, &target_path)?
+        } else {
+            vec![]
+        };
+        self.dep_names.insert(
+            dep_name.into(),
+            requirements.iter().map(|(name, _)| name.clone()).collect(),
+        );
+        for (child_name, child_requirement) in requirements {
+            if let Some(requirement) = self.requirements.get(&child_name) {
+                if !self.root_dep_names.contains(&child_name) {
+                    self.check_compatibility(&child_name, requirement, &child_requirement)?;
+                }
+            } else {
+                self.requirements
+                    .insert(child_name.clone(), child_requirement);
+                self.resolve(&child_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_compatibility(
+        &self,
+        dep_name: &str,
+        requirement: &Requirement,
+        other_requirement: &Requirement,
+    ) -> Result<(), Error> {
+        let version = self.versions.get(dep_name);
+        let is_same_registry = other_requirement.registry.is_some()
+            && other_requirement.registry == requirement.registry;
+        if !is_same_registry && requirement.source != other_requirement.source {
+            return Err(Error::Conflict(
+                dep_name.into(),
+                requirement.source.to_string(),
+                other_requirement.source.to_string(),
+            ));
+        }
+        if let Some(other_version) = &other_requirement.version {
+            check_version(dep_name, other_version, version)?;
+        }
+        Ok(())
+    }
+
+    /// Retrieves a dependency and returns its folder.
+    ///
+    /// If a temporary folder is configured, the `_` folder is not modified: path dependencies
+    /// are read from their source folder, and other dependencies missing from the `_` folder
+    /// or to update are retrieved in the temporary folder.
+    fn retrieve(&mut self, dep_name: &str) -> Result<PathBuf, Error> {
+        let target_parent_path = self.folder_path.join(TARGET_FOLDER_NAME);
+        let mut target_path = target_parent_path.join(dep_name);
+        match &self.requirements[dep_name].source {
+            Source::Path(source_path) => {
+                if !target_path.exists() {
+                    if self.tmp_folder_path.is_some() {
+                        target_path.clone_from(source_path);
+                    } else {
+                        create_folder(&target_parent_path)?;
+                        source::link_local_dependency(&target_path, source_path)?;
+                    }
+                }
+            }
+            source @ (Source::Url { .. } | Source::Git { .. }) => {
+                let locked = self.old_lock.dependencies.get(dep_name);
+                let is_updated = self.options.update.is_updated(dep_name)
+                    || locked.is_some_and(|locked| !source.is_locked_by(locked));
+                if let Some(tmp_folder_path) = self.tmp_folder_path {
+                    if is_updated || !target_path.exists() {
+                        target_path = tmp_folder_path.join(dep_name);
+                    }
+                } else {
+                    create_folder(&target_parent_path)?;
+                }
+                let locked = self.retrieve_locked(
+                    &target_path,
+                    source,
+                    dep_name,
+                    locked.filter(|_| !is_updated),
+                    is_updated,
+                )?;
+                self.new_lock.dependencies.insert(dep_name.into(), locked);
+            }
+        }
+        Ok(fs::canonicalize(&target_path).unwrap_or(target_path))
+    }
+
+    fn retrieve_locked(
+        &self,
+        target_path: &Path,
+        source: &Source,
+        dep_name: &str,
+        locked: Option<&LockedDependency>,
+        is_updated: bool,
+    ) -> Result<LockedDependency, Error> {
+        if is_updated && target_path.exists() {
+            remove_dependency(target_path)?;
+        }
+        let locked_hash = locked.map(|locked| locked.hash.as_str());
+        let (retrieval, is_cached) = if target_path.exists() {
+            (None, true)
+        } else if let Some(retrieval) = (!is_updated || self.options.is_offline)
+            .then(|| self.cache.load(source, locked_hash, target_path))
+            .transpose()?
+            .flatten()
+        {
+            (Some(retrieval), true)
+        } else if self.options.is_offline {
+            return Err(Error::NotCached(dep_name.into()));
+        } else {
+            let locked_revision = locked.and_then(|locked| locked.revision.as_deref());
+            let retrieval = source.retrieve(target_path, dep_name, locked_revision)?;
+            (Some(retrieval), false)
+        };
+        let hash = hash::hash_folder(target_path)?;
+        if let Some(locked) = locked {
+            if locked.hash != hash {
+                if retrieval.is_some() {
+                    remove_dependency(target_path)?;
+                }
+                return Err(Error::HashMismatch(
+                    dep_name.into(),
+                    locked.hash.clone(),
+                    hash,
+                ));
+            }
+        }
+        if let (Some(retrieval), false) = (&retrieval, is_cached) {
+            self.cache.store(source, &hash, retrieval, target_path)?;
+        }
+        Ok(locked
+            .cloned()
+            .unwrap_or_else(|| source.locked(retrieval, hash)))
+    }
+
+    fn check_cycle(
+        &self,
+        dep_name: &str,
+        stack: &mut Vec<String>,
+        checked_dep_names: &mut HashSet<String>,
+    ) -> Result<(), Error> {
+        if let Some(index) = stack.iter().position(|name| name == dep_name) {
+            let mut cycle = stack[index..].to_vec();
+            cycle.push(dep_name.into());
+            return Err(Error::Cycle(cycle));
+        }
+        if !checked_dep_names.insert(dep_name.into()) {
+            return Ok(());
+        }
+        stack.push(dep_name.into());
+        for child_name in self.dep_names.get(dep_name).into_iter().flatten() {
+            self.check_cycle(child_name, stack, checked_dep_names)?;
+        }
+        stack.pop();
+        Ok(())
+    }
+}
+
+/// A dependency required by a configuration file.
+#[derive(Debug)]
+struct Requirement {
+    source: Source,
+    version: Option<VersionReq>,
+    registry: Option<String>,
+    registry_version: Option<Version>,
+}
+
+fn registry_key(location: &str, config_folder_path: &Path) -> String {
+    source::local_file_path(location, config_folder_path).map_or_else(
+        || location.trim_end_matches('/').into(),
+        |path| {
+            fs::canonicalize(&path)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned()
+        },
+    )
+}
+
+fn check_version(
+    dep_name: &str,
+    requirement: &VersionReq,
+    version: Option<&Version>,
+) -> Result<(), Error> {
+    if version.is_some_and(|version| requirement.matches(version)) {
+        Ok(())
+    } else {
+        Err(Error::VersionMismatch(
+            dep_name.into(),
+            requirement.to_string(),
+            version.map_or_else(|| "<none>".into(), Version::to_string),
+        ))
+    }
+}
+
+fn create_folder(path: &Path) -> Result<(), Error> {
+    fs::create_dir_all(path).map_err(|e| Error::Io(path.into(), e))
+}
+
+fn remove_dependency(target_path: &Path) -> Result<(), Error> {
+    fs::remove_dir_all(target_path).map_err(|e| Error::Io(target_path.into(), e))
+}
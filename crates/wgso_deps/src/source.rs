@@ -0,0 +1,477 @@
+use crate::config::DependencyConfig;
+use crate::git::GitReference;
+use crate::lock::LockedDependency;
+use crate::registry::RegistryPackage;
+use crate::Error;
+use crate::{archive, git, hash};
+use fs_extra::dir::CopyOptions;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The resolved source of a dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Source {
+    Path(PathBuf),
+    Url {
+        url: String,
+        archive_path: Option<PathBuf>,
+        subdir: Option<String>,
+        sha256: Option<String>,
+    },
+    Git {
+        repository: String,
+        repository_path: Option<PathBuf>,
+        reference: GitReference,
+        subdir: Option<String>,
+    },
+}
+
+impl Source {
+    pub(crate) fn new(
+        dep_name: &str,
+        config: DependencyConfig,
+        config_folder_path: &Path,
+        registry_package: Option<RegistryPackage>,
+    ) -> Result<Self, Error> {
+        if config.registry.is_some() && (config.url.is_some() || config.git.is_some()) {
+            return Err(Error::InvalidSource(
+                dep_name.into(),
+                "`url` and `git` keys cannot be used with `registry` key".into(),
+            ));
+        }
+        let package_subdir = registry_package.as_ref().and_then(|p| p.subdir.as_deref());
+        if [config.subdir.as_deref(), package_subdir]
+            .into_iter()
+            .flatten()
+            .any(|subdir| !is_relative_subfolder(subdir))
+        {
+            return Err(Error::InvalidSource(
+                dep_name.into(),
+                "`subdir` key must be a relative path without `..`".into(),
+            ));
+        }
+        if let Some(dep_path) = local_path(dep_name, &config, config_folder_path) {
+            Ok(Self::Path(fs::canonicalize(&dep_path).unwrap_or(dep_path)))
+        } else if let Some(repository) = config.git {
+            if config.url.is_some() || config.sha256.is_some() {
+                return Err(Error::InvalidSource(
+                    dep_name.into(),
+                    "`url` and `sha256` keys cannot be used with `git` key".into(),
+                ));
+            }
+            let reference = match (config.rev, config.tag, config.branch) {
+                (None, None, None) => GitReference::DefaultBranch,
+                (Some(rev), None, None) => GitReference::Rev(rev),
+                (None, Some(tag), None) => GitReference::Tag(tag),
+                (None, None, Some(branch)) => GitReference::Branch(branch),
+                _ => {
+                    return Err(Error::InvalidSource(
+                        dep_name.into(),
+                        "only one of `rev`, `tag` and `branch` keys can be defined".into(),
+                    ))
+                }
+            };
+            if repository.starts_with('-') || reference.name().is_some_and(|n| n.starts_with('-')) {
+                return Err(Error::InvalidSource(
+                    dep_name.into(),
+                    "Git repository and reference cannot start with `-`".into(),
+                ));
+            }
+            Ok(Self::Git {
+                repository_path: local_repository_path(&repository, config_folder_path),
+                repository,
+                reference,
+                subdir: config.subdir,
+            })
+        } else if let Some(url) = config.url {
+            Ok(Self::Url {
+                archive_path: local_file_path(&url, config_folder_path),
+                url,
+                subdir: config.subdir,
+                sha256: config.sha256.map(|hash| hash.to_lowercase()),
+            })
+        } else if let Some(package) = registry_package {
+            Ok(Self::Url {
+                archive_path: local_file_path(&package.url, config_folder_path),
+                url: package.url,
+                subdir: package.subdir,
+                sha256: package.sha256.map(|hash| hash.to_lowercase()),
+            })
+        } else {
+            Err(Error::NoDependencySource(dep_name.into()))
+        }
+    }
+
+    /// Returns the key identifying the source in the cache.
+    ///
+    /// `None` is returned for sources that are not cached because they are stored locally.
+    pub(crate) fn cache_key(&self) -> Option<String> {
+        match self {
+            Self::Path(_)
+            | Self::Url {
+                archive_path: Some(_),
+                ..
+            } => None,
+            Self::Url {
+                url,
+                archive_path: None,
+                subdir,
+                ..
+            } => Some(format!("url\n{url}\n{}", subdir.as_deref().unwrap_or(""))),
+            Self::Git {
+                repository,
+                repository_path,
+                reference,
+                subdir,
+            } => Some(format!(
+                "git\n{}\n{reference}\n{}",
+                repository_path
+                    .as_ref()
+                    .map_or_else(|| repository.clone(), |path| path.display().to_string()),
+                subdir.as_deref().unwrap_or("")
+            )),
+        }
+    }
+
+    pub(crate) fn is_locked_by(&self, locked: &LockedDependency) -> bool {
+        match self {
+            Self::Path(_) => false,
+            Self::Url {
+                url,
+                subdir,
+                sha256,
+                ..
+            } => {
+                url == &locked.url
+                    && locked.reference.is_none()
+                    && subdir == &locked.subdir
+                    && sha256 == &locked.sha256
+            }
+            Self::Git {
+                repository,
+                reference,
+                subdir,
+                ..
+            } => {
+                repository == &locked.url
+                    && Some(reference.to_string()) == locked.reference
+                    && subdir == &locked.subdir
+                    && locked.sha256.is_none()
+            }
+        }
+    }
+
+    /// Retrieves a remote dependency in `target_path`.
+    ///
+    /// If `revision` is defined, then this revision is retrieved instead of the configured one.
+    pub(crate) fn retrieve(
+        &self,
+        target_path: &Path,
+        dep_name: &str,
+        revision: Option<&str>,
+    ) -> Result<Retrieval, Error> {
+        match self {
+            Self::Path(_) => unreachable!("internal error: local dependencies are not retrieved"),
+            Self::Url {
+                url,
+                archive_path,
+                subdir,
+                sha256,
+            } => Ok(Retrieval {
+                resolved_url: retrieve_url_dependency(
+                    target_path,
+                    dep_name,
+                    (url, archive_path.as_deref()),
+                    subdir.as_deref(),
+                    sha256.as_deref(),
+                )?,
+                revision: None,
+            }),
+            Self::Git {
+                repository,
+                repository_path,
+                reference,
+                subdir,
+            } => {
+                let subdir = subdir.as_deref().unwrap_or(dep_name);
+                Ok(Retrieval {
+                    resolved_url: repository.clone(),
+                    revision: Some(retrieve_git_dependency(
+                        target_path,
+                        (repository, repository_path.as_deref()),
+                        reference,
+                        revision,
+                        Path::new(subdir),
+                    )?),
+                })
+            }
+        }
+    }
+
+    /// Returns the lock entry of a remote dependency.
+    ///
+    /// `retrieval` is `None` if the dependency files were already retrieved.
+    pub(crate) fn locked(&self, retrieval: Option<Retrieval>, hash: String) -> LockedDependency {
+        let (url, reference, subdir, sha256) = match self {
+            Self::Path(_) => unreachable!("internal error: local dependencies are not locked"),
+            Self::Url {
+                url,
+                subdir,
+                sha256,
+                ..
+            } => (url, None, subdir, sha256.clone()),
+            Self::Git {
+                repository,
+                reference,
+                subdir,
+                ..
+            } => (repository, Some(reference.to_string()), subdir, None),
+        };
+        let (resolved_url, revision) = retrieval.map_or_else(
+            || (url.clone(), None),
+            |retrieval| (retrieval.resolved_url, retrieval.revision),
+        );
+        LockedDependency {
+            url: url.clone(),
+            reference,
+            subdir: subdir.clone(),
+            sha256,
+            version: None,
+            resolved_url,
+            revision,
+            hash,
+            retrieved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "path {}", path.display()),
+            Self::Url { url, .. } => write!(f, "URL {url}"),
+            Self::Git {
+                repository,
+                reference,
+                ..
+            } => write!(f, "Git repository {repository} ({reference})"),
+        }?;
+        match self {
+            Self::Path(_) => Ok(()),
+            Self::Url { subdir, .. } | Self::Git { subdir, .. } => {
+                if let Some(subdir) = subdir {
+                    write!(f, " in folder {subdir}")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// The result of a remote dependency retrieval.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Retrieval {
+    resolved_url: String,
+    revision: Option<String>,
+}
+
+pub(crate) fn link_local_dependency(target_path: &Path, dep_path: &Path) -> Result<(), Error> {
+    #[cfg(target_family = "unix")]
+    {
+        let source_path = std::env::current_dir()
+            .map_err(|e| Error::Io("<current folder>".into(), e))?
+            .join(dep_path);
+        std::os::unix::fs::symlink(&source_path, target_path).map_err(|e| Error::Io(source_path, e))
+    }
+    #[cfg(target_family = "windows")]
+    {
+        let source_path = std::env::current_dir()
+            .map_err(|e| Error::Io("<current folder>".into(), e))?
+            .join(dep_path);
+        std::os::windows::fs::symlink_dir(&source_path, target_path)
+            .map_err(|e| Error::Io(source_path, e))
+    }
+    #[cfg(not(any(target_family = "unix", target_family = "windows")))]
+    {
+        copy_local_dependency(target_path, dep_path)
+    }
+}
+
+/// Copies the content of a dependency folder, except the `.git` folder.
+pub(crate) fn copy_local_dependency(target_path: &Path, source_path: &Path) -> Result<(), Error> {
+    let item_paths = source_path
+        .read_dir()
+        .map_err(|e| Error::Io(source_path.into(), e))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref()
+                .map_or(true, |path| !hash::is_excluded(path, source_path))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| Error::Io(source_path.into(), e))?;
+    fs::create_dir_all(target_path).map_err(|e| Error::Io(target_path.into(), e))?;
+    fs_extra::copy_items(&item_paths, target_path, &CopyOptions::new())
+        .map(|_| ())
+        .map_err(|e| Error::Copy(source_path.into(), target_path.into(), e))
+}
+
+/// Returns the local folder of a dependency if it should be retrieved from a local path.
+///
+/// The local path is used if it exists or if no remote source is configured.
+pub(crate) fn local_path(
+    dep_name: &str,
+    config: &DependencyConfig,
+    config_folder_path: &Path,
+) -> Option<PathBuf> {
+    let is_remote = config.url.is_some() || config.git.is_some() || config.registry.is_some();
+    config
+        .path
+        .as_ref()
+        .map(|path| config_folder_path.join(path).join(dep_name))
+        .filter(|path| path.is_dir() || !is_remote)
+}
+
+/// Returns the path of the file if the URL targets a local file.
+///
+/// The URL can be a `file://` URL or a path relative to the configuration folder.
+pub(crate) fn local_file_path(url: &str, config_folder_path: &Path) -> Option<PathBuf> {
+    if url.starts_with("file://") {
+        file_url_path(url)
+    } else if url.contains("://") {
+        None
+    } else {
+        Some(config_folder_path.join(url))
+    }
+}
+
+/// Returns the local path of a Git repository, relative to the configuration folder.
+///
+/// `None` is returned for remote repositories, including the ones using SSH syntax
+/// (e.g. `git@github.com:user/repository.git`).
+pub(crate) fn local_repository_path(
+    repository: &str,
+    config_folder_path: &Path,
+) -> Option<PathBuf> {
+    let is_ssh = !Path::new(repository).is_absolute()
+        && repository
+            .split_once(':')
+            .is_some_and(|(host, _)| !host.contains('/'));
+    if is_ssh {
+        None
+    } else {
+        local_file_path(repository, config_folder_path)
+            .map(|path| fs::canonicalize(&path).unwrap_or(path))
+    }
+}
+
+fn is_relative_subfolder(subdir: &str) -> bool {
+    Path::new(subdir)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    reqwest::Url::parse(url).ok()?.to_file_path().ok()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn file_url_path(url: &str) -> Option<PathBuf> {
+    url.strip_prefix("file://").map(PathBuf::from)
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn retrieve_url_dependency(
+    target_path: &Path,
+    dep_name: &str,
+    (url, archive_path): (&str, Option<&Path>),
+    subdir: Option<&str>,
+    sha256: Option<&str>,
+) -> Result<String, Error> {
+    let tmp_folder = tempfile::TempDir::new().map_err(|e| Error::Io("temp folder".into(), e))?;
+    let extracted_path = tmp_folder.path().join("files");
+    let (archive_path, resolved_url) = if let Some(archive_path) = archive_path {
+        (archive_path.to_path_buf(), url.into())
+    } else {
+        let archive_path = tmp_folder.path().join("archive");
+        let mut response = reqwest::blocking::get(url).map_err(Error::Request)?;
+        let resolved_url = response.url().to_string();
+        let mut archive_file =
+            fs::File::create(&archive_path).map_err(|e| Error::Io(archive_path.clone(), e))?;
+        std::io::copy(&mut response, &mut archive_file)
+            .map_err(|e| Error::Io(archive_path.clone(), e))?;
+        (archive_path, resolved_url)
+    };
+    if let Some(sha256) = sha256 {
+        let archive_hash = hash::hash_file(&archive_path)?;
+        if archive_hash != sha256 {
+            return Err(Error::ArchiveHashMismatch(
+                dep_name.into(),
+                sha256.into(),
+                archive_hash,
+            ));
+        }
+    }
+    archive::extract(&archive_path, &extracted_path, url)?;
+    let source_path = if let Some(subdir) = subdir {
+        extracted_path.join(subdir)
+    } else {
+        extracted_path
+            .read_dir()
+            .map_err(|e| Error::Io(extracted_path.clone(), e))?
+            .filter_map(Result::ok)
+            .find(|entry| entry.path().is_dir())
+            .map_or(extracted_path, |entry| entry.path())
+            .join(dep_name)
+    };
+    copy_local_dependency(target_path, &source_path)?;
+    Ok(resolved_url)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn retrieve_url_dependency(
+    _target_path: &Path,
+    _dep_name: &str,
+    _location: (&str, Option<&Path>),
+    _subdir: Option<&str>,
+    _sha256: Option<&str>,
+) -> Result<String, Error> {
+    panic!("`wgso_deps` crate is only supported on Window, Linux and macOS")
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn retrieve_git_dependency(
+    target_path: &Path,
+    (repository, repository_path): (&str, Option<&Path>),
+    reference: &GitReference,
+    revision: Option<&str>,
+    subdir: &Path,
+) -> Result<String, Error> {
+    let tmp_folder = tempfile::TempDir::new().map_err(|e| Error::Io("temp folder".into(), e))?;
+    let clone_path = tmp_folder.path().join("repository");
+    let repository = repository_path.map_or_else(
+        || repository.into(),
+        |path| path.to_string_lossy().into_owned(),
+    );
+    let revision = git::clone(&repository, reference, revision, &clone_path)?;
+    copy_local_dependency(target_path, &clone_path.join(subdir))?;
+    Ok(revision)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn retrieve_git_dependency(
+    _target_path: &Path,
+    _repository: (&str, Option<&Path>),
+    _reference: &GitReference,
+    _revision: Option<&str>,
+    _subdir: &Path,
+) -> Result<String, Error> {
+    panic!("`wgso_deps` crate is only supported on Window, Linux and macOS")
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// A resolved tree of dependencies.
+///
+/// The [`Display`] implementation shows the tree with one dependency per line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyTree {
+    /// The names of the dependencies listed in the root configuration file.
+    pub root_dependencies: Vec<String>,
+    /// The resolved dependencies by name.
+    pub dependencies: BTreeMap<String, ResolvedDependency>,
+}
+
+impl DependencyTree {
+    fn fmt_dependency(
+        &self,
+        f: &mut Formatter<'_>,
+        dep_name: &str,
+        (prefix, child_prefix): (&str, &str),
+    ) -> fmt::Result {
+        let Some(dependency) = self.dependencies.get(dep_name) else {
+            return Ok(());
+        };
+        write!(f, "{prefix}{dep_name}")?;
+        if let Some(version) = &dependency.version {
+            write!(f, " v{version}")?;
+        }
+        writeln!(f, " ({})", dependency.source)?;
+        for (index, child_name) in dependency.dependencies.iter().enumerate() {
+            let (branch, indent) = if index + 1 == dependency.dependencies.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            self.fmt_dependency(
+                f,
+                child_name,
+                (
+                    &format!("{child_prefix}{branch}"),
+                    &format!("{child_prefix}{indent}"),
+                ),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for DependencyTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for dep_name in &self.root_dependencies {
+            self.fmt_dependency(f, dep_name, ("", ""))?;
+        }
+        Ok(())
+    }
+}
+
+/// A resolved dependency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    /// The description of the dependency source.
+    pub source: String,
+    /// The version of the dependency, if defined in its `wgso.yaml` file.
+    pub version: Option<String>,
+    /// The names of the dependencies required by this dependency.
+    pub dependencies: Vec<String>,
+}
@@ -0,0 +1 @@
+const LIB = 1;
@@ -0,0 +1 @@
+const VERSIONED = 1;
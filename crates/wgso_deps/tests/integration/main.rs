@@ -1,7 +1,16 @@
 #![allow(missing_docs, clippy::unwrap_used)]
 
-use std::path::Path;
-use wgso_deps::Error;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use tempfile::TempDir;
+use wgso_deps::{Error, RetrieveOptions, UpdatePolicy};
 
 #[test]
 fn retrieve_valid_dependencies() {
@@ -9,12 +18,16 @@ fn retrieve_valid_dependencies() {
     let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
     let is_local_dep_retrieved = config_path.join("_/config/directives.yaml").is_file();
     let is_url_dep_retrieved = config_path.join("_/.github/dependabot.yml").is_file();
+    let is_lock_created = config_path.join("wgso.lock").is_file();
     fs_extra::remove_items(&[config_path.join("_")]).unwrap();
     assert!(result.is_ok());
     assert!(is_local_dep_retrieved);
     assert!(is_url_dep_retrieved);
+    assert!(is_lock_created);
     // Check retrieval of existing dependencies:
-    assert!(wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml")).is_ok());
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs_extra::remove_items(&[config_path.join("_"), config_path.join("wgso.lock")]).unwrap();
+    assert!(result.is_ok());
 }
 
 #[test]
@@ -22,7 +35,7 @@ fn retrieve_valid_dependencies_with_url_fallback() {
     let config_path = Path::new("tests/configs/valid_fallback");
     let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
     let is_dep_retrieved = config_path.join("_/.github/dependabot.yml").is_file();
-    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    fs_extra::remove_items(&[config_path.join("_"), config_path.join("wgso.lock")]).unwrap();
     assert!(result.is_ok());
     assert!(is_dep_retrieved);
 }
@@ -46,3 +59,589 @@ fn retrieve_dependencies_from_missing_config_file() {
     assert!(result.is_ok());
     assert!(!is_folder_created);
 }
+
+#[test]
+fn retrieve_dependencies_with_new_lock() {
+    let config_path = Path::new("tests/configs/locked");
+    create_retrieved_dependency(config_path);
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    let lock = fs::read_to_string(config_path.join("wgso.lock")).unwrap_or_default();
+    let second_result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs::write(config_path.join("_/dep/lib.wgsl"), "const VALUE = 2;").unwrap();
+    let modified_result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs_extra::remove_items(&[config_path.join("_"), config_path.join("wgso.lock")]).unwrap();
+    assert!(result.is_ok());
+    assert!(lock.contains("url: https://localhost.invalid/dep.zip"));
+    assert!(lock.contains("hash: sha256:"));
+    assert!(second_result.is_ok());
+    assert!(matches!(modified_result, Err(Error::HashMismatch(_, _, _))));
+}
+
+#[test]
+fn retrieve_dependencies_with_mismatching_lock() {
+    let config_path = Path::new("tests/configs/locked_mismatch");
+    create_retrieved_dependency(config_path);
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    let is_dep_kept = config_path.join("_/dep/lib.wgsl").is_file();
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(matches!(result, Err(Error::HashMismatch(_, _, _))));
+    assert!(is_dep_kept);
+}
+
+fn create_retrieved_dependency(config_path: &Path) {
+    fs::create_dir_all(config_path.join("_/dep")).unwrap();
+    fs::write(config_path.join("_/dep/lib.wgsl"), "const VALUE = 1;").unwrap();
+}
+
+#[test]
+fn retrieve_transitive_dependencies() {
+    let config_path = Path::new("tests/configs/transitive");
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    let is_dep_retrieved = config_path.join("_/lib/lib.wgsl").is_file();
+    let is_transitive_dep_retrieved = config_path.join("_/base/base.wgsl").is_file();
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(result.is_ok());
+    assert!(is_dep_retrieved);
+    assert!(is_transitive_dep_retrieved);
+}
+
+#[test]
+fn retrieve_conflicting_transitive_dependencies() {
+    let config_path = Path::new("tests/configs/conflict");
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(matches!(result, Err(Error::Conflict(name, _, _)) if name == "base"));
+}
+
+#[test]
+fn retrieve_conflicting_transitive_dependencies_overridden_by_root() {
+    let config_path = Path::new("tests/configs/conflict_override");
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    let base = fs::read_to_string(config_path.join("_/base/base.wgsl")).unwrap_or_default();
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(result.is_ok());
+    assert_eq!(base, "const BASE = 2;\n");
+}
+
+#[test]
+fn retrieve_cyclic_dependencies() {
+    let config_path = Path::new("tests/configs/cycle");
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(
+        matches!(result, Err(Error::Cycle(cycle)) if cycle == ["cycle_a", "cycle_b", "cycle_a"])
+    );
+}
+
+#[test]
+fn retrieve_dependencies_with_stale_folder() {
+    let config_path = Path::new("tests/configs/stale");
+    fs::create_dir_all(config_path.join("_/removed")).unwrap();
+    fs::write(config_path.join("_/removed/removed.wgsl"), "").unwrap();
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    let is_stale_folder_removed = !config_path.join("_/removed").exists();
+    let is_source_kept = Path::new("tests/deps/base/base.wgsl").is_file();
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(result.is_ok());
+    assert!(is_stale_folder_removed);
+    assert!(is_source_kept);
+}
+
+#[test]
+fn resolve_dependency_tree() {
+    let config_path = Path::new("tests/configs/tree");
+    let result =
+        wgso_deps::resolve_dependencies(config_path.join("wgso.yaml"), &RetrieveOptions::default());
+    assert!(!config_path.join("_").exists());
+    assert!(!config_path.join("wgso.lock").exists());
+    let tree = result.unwrap();
+    assert_eq!(tree.root_dependencies, ["base", "lib"]);
+    assert_eq!(tree.dependencies["lib"].dependencies, ["base"]);
+    assert!(tree.dependencies["base"].dependencies.is_empty());
+    let lines: Vec<_> = tree
+        .to_string()
+        .lines()
+        .map(|line| line.split(" (").next().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(lines, ["base", "lib", "└── base"]);
+    assert!(tree.to_string().starts_with("base (path "));
+}
+
+#[test]
+fn retrieve_git_dependencies() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!(
+        "dependencies:\n  lib:\n    git: {repository}\n  \
+        lib_v1:\n    git: {repository}\n    tag: v1.0\n    subdir: lib\n"
+    );
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let result =
+        wgso_deps::retrieve_dependencies_with_options(folder.path().join("wgso.yaml"), &options);
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "v2");
+    assert_eq!(read_dep_file(folder.path(), "lib_v1"), "v1");
+    let lock = fs::read_to_string(folder.path().join("wgso.lock")).unwrap();
+    assert!(lock.contains("reference: tag v1.0"));
+    assert!(lock.contains("revision: "));
+}
+
+#[test]
+fn retrieve_git_dependencies_with_locked_revision() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!("dependencies:\n  lib:\n    git: {repository}\n");
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let config_path = folder.path().join("wgso.yaml");
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    wgso_deps::retrieve_dependencies_with_options(&config_path, &options).unwrap();
+    commit_file(&folder.path().join("work"), "v3", None);
+    run_git(
+        &folder.path().join("work"),
+        &["push", "--quiet", "origin", "HEAD"],
+    );
+    fs::remove_dir_all(folder.path().join("_")).unwrap();
+    let options = cached_options(&folder.path().join("other"), UpdatePolicy::Locked, false);
+    let locked_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    let locked_content = read_dep_file(folder.path(), "lib");
+    let options = cached_options(folder.path(), UpdatePolicy::All, false);
+    let updated_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(locked_result.is_ok());
+    assert_eq!(locked_content, "v2");
+    assert!(updated_result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "v3");
+}
+
+#[test]
+fn retrieve_git_dependencies_from_repository_root() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!("dependencies:\n  lib:\n    git: {repository}\n    subdir: .\n");
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let config_path = folder.path().join("wgso.yaml");
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    let has_git_folder = folder.path().join("_/lib/.git").exists();
+    fs::remove_dir_all(folder.path().join("_")).unwrap();
+    let options = cached_options(&folder.path().join("other"), UpdatePolicy::Locked, false);
+    let locked_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(result.is_ok());
+    assert!(!has_git_folder);
+    assert!(locked_result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib/lib"), "v2");
+}
+
+#[test]
+fn retrieve_dependencies_with_invalid_subdir() {
+    for subdir in ["../lib", "lib/../..", "/lib"] {
+        let folder = TempDir::new().unwrap();
+        let config =
+            format!("dependencies:\n  lib:\n    git: repository.git\n    subdir: {subdir}\n");
+        fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+        let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+        assert!(matches!(result, Err(Error::InvalidSource(name, _)) if name == "lib"));
+        assert!(!folder.path().join("_").exists());
+    }
+}
+
+#[test]
+fn resolve_dependency_tree_without_modifying_program_folder() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!("dependencies:\n  lib:\n    git: {repository}\n");
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    fs::create_dir_all(folder.path().join("_/stale")).unwrap();
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let result = wgso_deps::resolve_dependencies(folder.path().join("wgso.yaml"), &options);
+    let tree = result.unwrap();
+    assert_eq!(tree.root_dependencies, ["lib"]);
+    assert!(folder.path().join("_/stale").exists());
+    assert!(!folder.path().join("_/lib").exists());
+    assert!(!folder.path().join("wgso.lock").exists());
+}
+
+#[test]
+fn update_unknown_dependency() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!("dependencies:\n  lib:\n    git: {repository}\n");
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let config_path = folder.path().join("wgso.yaml");
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    wgso_deps::retrieve_dependencies_with_options(&config_path, &options).unwrap();
+    fs::remove_file(folder.path().join("wgso.lock")).unwrap();
+    fs::create_dir(folder.path().join("_/stale")).unwrap();
+    let options = cached_options(
+        folder.path(),
+        UpdatePolicy::Only(vec!["other".into()]),
+        false,
+    );
+    let result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(matches!(result, Err(Error::UnknownDependency(name)) if name == "other"));
+    assert_eq!(read_dep_file(folder.path(), "lib"), "v2");
+    assert!(folder.path().join("_/stale").is_dir());
+    assert!(!folder.path().join("wgso.lock").exists());
+}
+
+#[test]
+fn retrieve_cached_dependencies_in_offline_mode() {
+    let folder = TempDir::new().unwrap();
+    let repository = create_git_repository(folder.path());
+    let config = format!("dependencies:\n  lib:\n    git: {repository}\n");
+    for program in ["first", "second"] {
+        fs::create_dir_all(folder.path().join(program)).unwrap();
+        fs::write(folder.path().join(program).join("wgso.yaml"), &config).unwrap();
+    }
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let first_config_path = folder.path().join("first/wgso.yaml");
+    wgso_deps::retrieve_dependencies_with_options(&first_config_path, &options).unwrap();
+    fs::remove_dir_all(&repository).unwrap();
+    let options = cached_options(folder.path(), UpdatePolicy::All, true);
+    let second_config_path = folder.path().join("second/wgso.yaml");
+    let result = wgso_deps::retrieve_dependencies_with_options(&second_config_path, &options);
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(&folder.path().join("second"), "lib"), "v2");
+    let options = cached_options(&folder.path().join("empty"), UpdatePolicy::Locked, true);
+    fs::remove_dir_all(folder.path().join("second/_")).unwrap();
+    let result = wgso_deps::retrieve_dependencies_with_options(&second_config_path, &options);
+    assert!(matches!(result, Err(Error::NotCached(name)) if name == "lib"));
+}
+
+#[test]
+fn retrieve_git_dependencies_with_relative_repository_path() {
+    let folder = TempDir::new().unwrap();
+    create_git_repository(folder.path());
+    let program_path = folder.path().join("program");
+    fs::create_dir_all(&program_path).unwrap();
+    let config = "dependencies:\n  lib:\n    git: ../repository.git\n";
+    fs::write(program_path.join("wgso.yaml"), config).unwrap();
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let result =
+        wgso_deps::retrieve_dependencies_with_options(program_path.join("wgso.yaml"), &options);
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(&program_path, "lib"), "v2");
+    let lock = fs::read_to_string(program_path.join("wgso.lock")).unwrap();
+    assert!(lock.contains("url: '../repository.git'"));
+}
+
+#[test]
+fn retrieve_git_dependencies_with_option_like_reference() {
+    let folder = TempDir::new().unwrap();
+    let config = "dependencies:\n  lib:\n    git: repository.git\n    branch: --help\n";
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(matches!(result, Err(Error::InvalidSource(name, _)) if name == "lib"));
+}
+
+#[test]
+fn retrieve_git_dependencies_with_invalid_reference() {
+    let folder = TempDir::new().unwrap();
+    let config = "dependencies:\n  lib:\n    git: repository.git\n    tag: v1.0\n    rev: abc\n";
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(matches!(result, Err(Error::InvalidSource(name, _)) if name == "lib"));
+}
+
+#[test]
+fn retrieve_tar_gz_dependency_from_relative_path() {
+    let folder = TempDir::new().unwrap();
+    let archive = create_tar_archive(
+        folder.path(),
+        "lib.tar.gz",
+        &[("project-1.0/lib/lib.wgsl", "archived")],
+        gz_encoder,
+    );
+    fs::write(
+        folder.path().join("wgso.yaml"),
+        "dependencies:\n  lib:\n    url: lib.tar.gz\n",
+    )
+    .unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "archived");
+    assert!(archive.is_file());
+}
+
+#[test]
+fn retrieve_tar_xz_dependency_from_file_url_with_sha256() {
+    let folder = TempDir::new().unwrap();
+    let archive = create_tar_archive(
+        folder.path(),
+        "lib.tar.xz",
+        &[("project-1.0/lib/lib.wgsl", "archived")],
+        |file| liblzma::write::XzEncoder::new(file, 6),
+    );
+    let config = format!(
+        "dependencies:\n  other:\n    url: file://{}\n    subdir: project-1.0/lib\n    \
+        sha256: {}\n",
+        archive.display(),
+        sha256(&archive).to_uppercase()
+    );
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "other"), "archived");
+}
+
+#[test]
+fn retrieve_zip_dependency_with_invalid_sha256() {
+    let folder = TempDir::new().unwrap();
+    let mut zip = zip::ZipWriter::new(File::create(folder.path().join("lib.zip")).unwrap());
+    zip.start_file(
+        "project-1.0/lib/lib.wgsl",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    zip.write_all(b"archived").unwrap();
+    zip.finish().unwrap();
+    let config = format!(
+        "dependencies:\n  lib:\n    url: lib.zip\n    sha256: {}\n",
+        "0".repeat(64)
+    );
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(matches!(result, Err(Error::ArchiveHashMismatch(_, _, _))));
+    assert!(!folder.path().join("_/lib").exists());
+}
+
+#[test]
+fn retrieve_dependency_with_unsupported_archive() {
+    let folder = TempDir::new().unwrap();
+    fs::write(folder.path().join("lib.txt"), "not an archive").unwrap();
+    fs::write(
+        folder.path().join("wgso.yaml"),
+        "dependencies:\n  lib:\n    url: lib.txt\n",
+    )
+    .unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(matches!(result, Err(Error::UnsupportedArchive(_))));
+}
+
+#[test]
+fn retrieve_dependencies_with_matching_version() {
+    let config_path = Path::new("tests/configs/version");
+    let result =
+        wgso_deps::resolve_dependencies(config_path.join("wgso.yaml"), &RetrieveOptions::default());
+    assert!(!config_path.join("_").exists());
+    let tree = result.unwrap();
+    assert_eq!(
+        tree.dependencies["versioned"].version.as_deref(),
+        Some("1.0.0")
+    );
+    assert!(tree.to_string().starts_with("versioned v1.0.0 (path "));
+}
+
+#[test]
+fn retrieve_dependencies_with_version_mismatch() {
+    let config_path = Path::new("tests/configs/version_mismatch");
+    let result = wgso_deps::retrieve_dependencies(config_path.join("wgso.yaml"));
+    fs_extra::remove_items(&[config_path.join("_")]).unwrap();
+    assert!(matches!(
+        result,
+        Err(Error::VersionMismatch(name, _, version)) if name == "versioned" && version == "1.0.0"
+    ));
+}
+
+#[test]
+fn retrieve_dependencies_from_registry() {
+    let folder = TempDir::new().unwrap();
+    create_registry(folder.path(), &["1.0.0", "1.2.0", "2.0.0"]);
+    let config = "dependencies:\n  lib:\n    registry: registry\n    version: ^1.0\n";
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let config_path = folder.path().join("wgso.yaml");
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "1.2.0");
+    let lock = fs::read_to_string(folder.path().join("wgso.lock")).unwrap();
+    assert!(lock.contains("version: '1.2.0'"));
+    create_registry(folder.path(), &["1.0.0", "1.2.0", "1.3.0", "2.0.0"]);
+    fs::remove_dir_all(folder.path().join("_")).unwrap();
+    let locked_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(locked_result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "1.2.0");
+    let options = cached_options(folder.path(), UpdatePolicy::All, false);
+    let updated_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    assert!(updated_result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "1.3.0");
+}
+
+#[test]
+fn retrieve_dependencies_from_http_registry_in_offline_mode() {
+    let folder = TempDir::new().unwrap();
+    create_registry(folder.path(), &["1.0.0", "1.2.0"]);
+    let registry_url = serve_folder(folder.path().join("registry"));
+    let config =
+        format!("dependencies:\n  lib:\n    registry: {registry_url}\n    version: ^1.0\n");
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let config_path = folder.path().join("wgso.yaml");
+    let offline_options = cached_options(folder.path(), UpdatePolicy::Locked, true);
+    let not_cached_result =
+        wgso_deps::retrieve_dependencies_with_options(&config_path, &offline_options);
+    let options = cached_options(folder.path(), UpdatePolicy::Locked, false);
+    let online_result = wgso_deps::retrieve_dependencies_with_options(&config_path, &options);
+    fs::remove_dir_all(folder.path().join("registry")).unwrap();
+    fs::remove_dir_all(folder.path().join("_")).unwrap();
+    let offline_result =
+        wgso_deps::retrieve_dependencies_with_options(&config_path, &offline_options);
+    assert!(matches!(not_cached_result, Err(Error::NotCached(name)) if name == "lib"));
+    assert!(online_result.is_ok());
+    assert!(offline_result.is_ok());
+    assert_eq!(read_dep_file(folder.path(), "lib"), "1.2.0");
+}
+
+#[test]
+fn retrieve_dependencies_from_registry_without_matching_version() {
+    let folder = TempDir::new().unwrap();
+    create_registry(folder.path(), &["1.0.0"]);
+    let config = "dependencies:\n  lib:\n    registry: registry\n    version: ^2.0\n";
+    fs::write(folder.path().join("wgso.yaml"), config).unwrap();
+    let result = wgso_deps::retrieve_dependencies(folder.path().join("wgso.yaml"));
+    assert!(matches!(result, Err(Error::PackageNotFound(name, _, _)) if name == "lib"));
+}
+
+fn create_tar_archive<W: Write>(
+    folder_path: &Path,
+    name: &str,
+    files: &[(&str, &str)],
+    encoder: impl FnOnce(File) -> W,
+) -> PathBuf {
+    let archive_path = folder_path.join(name);
+    let mut builder = tar::Builder::new(encoder(File::create(&archive_path).unwrap()));
+    for (path, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, content.as_bytes())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().flush().unwrap();
+    archive_path
+}
+
+fn create_registry(folder_path: &Path, versions: &[&str]) {
+    let registry_path = folder_path.join("registry");
+    fs::create_dir_all(&registry_path).unwrap();
+    let mut index = "packages:\n  lib:\n".to_string();
+    for version in versions {
+        let archive_name = format!("lib-{version}.tar.gz");
+        create_tar_archive(
+            &registry_path,
+            &archive_name,
+            &[
+                (&format!("lib-{version}/lib/lib.wgsl"), version),
+                (
+                    &format!("lib-{version}/lib/wgso.yaml"),
+                    &format!("program:\n  version: {version}\n"),
+                ),
+            ],
+            gz_encoder,
+        );
+        writeln!(index, "    - version: {version}\n      url: {archive_name}").unwrap();
+    }
+    fs::write(registry_path.join("index.yaml"), index).unwrap();
+}
+
+/// Serves the files of a folder over HTTP and returns the server URL.
+fn serve_folder(folder_path: PathBuf) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for mut stream in listener.incoming().map_while(Result::ok) {
+            let mut request_line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .unwrap();
+            let path = request_line.split(' ').nth(1).unwrap_or("/");
+            let response = fs::read(folder_path.join(path.trim_start_matches('/'))).map_or_else(
+                |_| b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec(),
+                |content| {
+                    let header = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n",
+                        content.len()
+                    );
+                    [header.into_bytes(), content].concat()
+                },
+            );
+            let _ = stream.write_all(&response);
+        }
+    });
+    url
+}
+
+fn gz_encoder(file: File) -> flate2::write::GzEncoder<File> {
+    flate2::write::GzEncoder::new(file, flate2::Compression::default())
+}
+
+fn sha256(path: &Path) -> String {
+    Sha256::digest(fs::read(path).unwrap())
+        .iter()
+        .fold(String::new(), |mut hash, byte| {
+            let _ = write!(hash, "{byte:02x}");
+            hash
+        })
+}
+
+fn cached_options(folder_path: &Path, update: UpdatePolicy, is_offline: bool) -> RetrieveOptions {
+    RetrieveOptions {
+        update,
+        is_offline,
+        cache_path: Some(folder_path.join("cache")),
+    }
+}
+
+fn create_git_repository(folder_path: &Path) -> String {
+    let work_path = folder_path.join("work");
+    let repository_path = folder_path.join("repository.git");
+    fs::create_dir_all(&work_path).unwrap();
+    run_git(&work_path, &["init", "--quiet"]);
+    commit_file(&work_path, "v1", Some("v1.0"));
+    commit_file(&work_path, "v2", None);
+    run_git(
+        folder_path,
+        &["clone", "--quiet", "--bare", "work", "repository.git"],
+    );
+    run_git(
+        &work_path,
+        &["remote", "add", "origin", "../repository.git"],
+    );
+    repository_path.to_str().unwrap().into()
+}
+
+fn commit_file(work_path: &Path, content: &str, tag: Option<&str>) {
+    fs::create_dir_all(work_path.join("lib")).unwrap();
+    fs::write(work_path.join("lib/lib.wgsl"), content).unwrap();
+    run_git(work_path, &["add", "."]);
+    run_git(
+        work_path,
+        &[
+            "-c",
+            "user.name=wgso",
+            "-c",
+            "user.email=wgso@example.com",
+            "commit",
+            "--quiet",
+            "-m",
+            content,
+        ],
+    );
+    if let Some(tag) = tag {
+        run_git(work_path, &["tag", tag]);
+    }
+}
+
+fn run_git(folder_path: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(folder_path)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn read_dep_file(folder_path: &Path, dep_name: &str) -> String {
+    fs::read_to_string(folder_path.join("_").join(dep_name).join("lib.wgsl")).unwrap()
+}
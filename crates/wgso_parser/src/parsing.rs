@@ -115,8 +115,8 @@ fn is_valid_pattern(rule: &PatternRule, token: &Token) -> bool {
     if rule.config.min.is_some() || rule.config.max.is_some() {
         match token.slice.parse::<i128>() {
             Ok(value) => {
-                rule.config.min.map_or(true, |min| value >= min)
-                    && rule.config.max.map_or(true, |max| value <= max)
+                rule.config.min.is_none_or(|min| value >= min)
+                    && rule.config.max.is_none_or(|max| value <= max)
             }
             Err(_) => false,
         }
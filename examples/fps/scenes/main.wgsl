@@ -24,6 +24,8 @@ fn main_() {
     main.camera = init_camera();
     main.plane = init_plane();
     main.rectangle_vertices = rectangle_vertices();
+    std_.window.is_cursor_hidden = 1;
+    std_.window.is_cursor_grabbed = 1;
 }
 
 #shader<compute> update
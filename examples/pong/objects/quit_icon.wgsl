@@ -0,0 +1,77 @@
+#mod main
+
+struct QuitIcon {
+    position: vec3f,
+    size: f32,
+    button_state: u32,
+}
+
+#mod compute
+#import ~.main
+#import _.std.ui.main
+
+fn init_quit_icon(position: vec3f, size: f32) -> QuitIcon {
+    return QuitIcon(position, size, BUTTON_STATE_NONE);
+}
+
+#shader<render, Vertex, QuitIcon> render
+#import ~.main
+#import constant.main
+#import _.std.color.constant
+#import _.std.math.distance
+#import _.std.io.main
+#import _.std.ui.main
+#import _.std.vertex.transform
+#import _.std.vertex.type
+
+const COLOR = CYAN;
+const CROSS_HALF_SIZE = 0.3;
+const THICKNESS = 0.08;
+
+var<uniform> surface: Surface;
+
+struct Fragment {
+    @builtin(position)
+    clip_position: vec4f,
+    @location(0)
+    relative_position: vec2f,
+    @location(1)
+    brightness: f32,
+}
+
+@vertex
+fn vs_main(vertex: Vertex, instance: QuitIcon) -> Fragment {
+    let scale_factor = scale_factor(surface.size, VISIBLE_AREA_MIN_SIZE);
+    let position = vertex.position.xy * instance.size + instance.position.xy;
+    return Fragment(
+        vec4f(position * scale_factor, instance.position.z, 1),
+        vertex.position.xy,
+        brightness(instance.button_state),
+    );
+}
+
+@fragment
+fn fs_main(fragment: Fragment) -> @location(0) vec4f {
+    let dist = min(
+        segment_signed_dist(fragment.relative_position, vec2f(-CROSS_HALF_SIZE), vec2f(CROSS_HALF_SIZE)),
+        segment_signed_dist(
+            fragment.relative_position,
+            vec2f(-CROSS_HALF_SIZE, CROSS_HALF_SIZE),
+            vec2f(CROSS_HALF_SIZE, -CROSS_HALF_SIZE),
+        ),
+    );
+    if dist < THICKNESS {
+        return vec4f(fragment.brightness, COLOR.gb, 1.);
+    }
+    return INVISIBLE;
+}
+
+fn brightness(button_state: u32) -> f32 {
+    if (button_state == BUTTON_STATE_PRESSED) {
+        return 0.3;
+    } else if (button_state == BUTTON_STATE_HOVERED) {
+        return 0.15;
+    } else {
+        return 0;
+    }
+}
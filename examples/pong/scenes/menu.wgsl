@@ -1,6 +1,7 @@
 #mod main
 #import constant.main
 #import objects.player_icon.compute
+#import objects.quit_icon.compute
 #import _.std.ui.compute
 
 #init ~.init()
@@ -10,6 +11,8 @@
 #draw objects.button.render<vertices.rectangle, menu.multiplayer_button>(surface=std_.surface)
 #draw objects.player_icon.render<vertices.rectangle, menu.multiplayer_icon2>(surface=std_.surface)
 #draw objects.player_icon.render<vertices.rectangle, menu.multiplayer_icon1>(surface=std_.surface)
+#draw objects.button.render<vertices.rectangle, menu.quit_button>(surface=std_.surface)
+#draw objects.quit_icon.render<vertices.rectangle, menu.quit_icon>(surface=std_.surface)
 
 const BUTTON_Z = 0.9;
 const ICON_Z_MAX = 0.8;
@@ -21,6 +24,8 @@ struct Menu {
     multiplayer_button: UiButton,
     multiplayer_icon1: PlayerIcon,
     multiplayer_icon2: PlayerIcon,
+    quit_button: UiButton,
+    quit_icon: QuitIcon,
 }
 
 var<storage, read_write> menu: Menu;
@@ -30,16 +35,20 @@ var<storage, read_write> menu: Menu;
 
 const ICON_SIZE = 0.8;
 const BUTTON_SIZE = vec2f(0.85, 0.8);
+const QUIT_ICON_SIZE = 0.25;
+const QUIT_BUTTON_SIZE = vec2f(0.3, 0.25);
 
 @compute
 @workgroup_size(1, 1, 1)
 fn main() {
     menu = Menu(
-        init_ui_button(vec3f(-0.5, 0, BUTTON_Z), BUTTON_SIZE),
-        init_player_icon(vec3f(-0.5, 0, ICON_Z_MIN), ICON_SIZE),
-        init_ui_button(vec3f(0.5, 0, BUTTON_Z), BUTTON_SIZE),
-        init_player_icon(vec3f(0.45, 0, ICON_Z_MIN), ICON_SIZE),
-        init_player_icon(vec3f(0.55, 0, ICON_Z_MAX), ICON_SIZE),
+        init_ui_button(vec3f(-0.5, 0.15, BUTTON_Z), BUTTON_SIZE),
+        init_player_icon(vec3f(-0.5, 0.15, ICON_Z_MIN), ICON_SIZE),
+        init_ui_button(vec3f(0.5, 0.15, BUTTON_Z), BUTTON_SIZE),
+        init_player_icon(vec3f(0.45, 0.15, ICON_Z_MIN), ICON_SIZE),
+        init_player_icon(vec3f(0.55, 0.15, ICON_Z_MAX), ICON_SIZE),
+        init_ui_button(vec3f(0, -0.5, BUTTON_Z), QUIT_BUTTON_SIZE),
+        init_quit_icon(vec3f(0, -0.5, ICON_Z_MIN), QUIT_ICON_SIZE),
     );
 }
 
@@ -47,6 +56,7 @@ fn main() {
 #import ~.main
 #import constant.main
 #import scenes.orchestrator.main
+#import _.std.input.keyboard
 #import _.std.math.matrix
 #import _.std.vertex.transform
 
@@ -60,9 +70,14 @@ fn main() {
     menu.multiplayer_button = update_ui_button(menu.multiplayer_button, view_mat_arr);
     menu.multiplayer_icon1.button_state = menu.multiplayer_button.state;
     menu.multiplayer_icon2.button_state = menu.multiplayer_button.state;
+    menu.quit_button = update_ui_button(menu.quit_button, view_mat_arr);
+    menu.quit_icon.button_state = menu.quit_button.state;
     if menu.singleplayer_button.state == BUTTON_STATE_RELEASED {
         start_singleplayer();
     } else if menu.multiplayer_button.state == BUTTON_STATE_RELEASED {
         start_multiplayer();
+    } else if menu.quit_button.state == BUTTON_STATE_RELEASED
+        || is_just_pressed(std_.keyboard.keys[KB_ESCAPE]) {
+        send_event(EVENT_EXIT, array<u32, EVENT_PARAM_COUNT>());
     }
 }
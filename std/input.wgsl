@@ -510,3 +510,54 @@ const MS_BUTTON_FORWARD = 4;
 const MS_WHEEL_LINES = 0;
 /// Mouse wheel unit in pixels.
 const MS_WHEEL_PIXELS = 1;
+
+/// Gamepad constants.
+#mod gamepad
+
+/// Bottom action button (e.g. <kbd>A</kbd> on Xbox controllers, <kbd>✕</kbd> on PlayStation controllers).
+const GP_BUTTON_SOUTH = 0;
+/// Right action button (e.g. <kbd>B</kbd> on Xbox controllers, <kbd>○</kbd> on PlayStation controllers).
+const GP_BUTTON_EAST = 1;
+/// Top action button (e.g. <kbd>Y</kbd> on Xbox controllers, <kbd>△</kbd> on PlayStation controllers).
+const GP_BUTTON_NORTH = 2;
+/// Left action button (e.g. <kbd>X</kbd> on Xbox controllers, <kbd>□</kbd> on PlayStation controllers).
+const GP_BUTTON_WEST = 3;
+/// Left bumper.
+const GP_BUTTON_LEFT_BUMPER = 4;
+/// Right bumper.
+const GP_BUTTON_RIGHT_BUMPER = 5;
+/// Left trigger.
+const GP_BUTTON_LEFT_TRIGGER = 6;
+/// Right trigger.
+const GP_BUTTON_RIGHT_TRIGGER = 7;
+/// Select button.
+const GP_BUTTON_SELECT = 8;
+/// Start button.
+const GP_BUTTON_START = 9;
+/// Mode button (e.g. Xbox or PlayStation button).
+const GP_BUTTON_MODE = 10;
+/// Left stick button.
+const GP_BUTTON_LEFT_STICK = 11;
+/// Right stick button.
+const GP_BUTTON_RIGHT_STICK = 12;
+/// D-pad up button.
+const GP_BUTTON_DPAD_UP = 13;
+/// D-pad down button.
+const GP_BUTTON_DPAD_DOWN = 14;
+/// D-pad left button.
+const GP_BUTTON_DPAD_LEFT = 15;
+/// D-pad right button.
+const GP_BUTTON_DPAD_RIGHT = 16;
+
+/// Horizontal axis of the left stick, positive to the right.
+const GP_AXIS_LEFT_STICK_X = 0;
+/// Vertical axis of the left stick, positive to the top.
+const GP_AXIS_LEFT_STICK_Y = 1;
+/// Horizontal axis of the right stick, positive to the right.
+const GP_AXIS_RIGHT_STICK_X = 2;
+/// Vertical axis of the right stick, positive to the top.
+const GP_AXIS_RIGHT_STICK_Y = 3;
+/// Left trigger axis, between 0 and 1.
+const GP_AXIS_LEFT_TRIGGER = 4;
+/// Right trigger axis, between 0 and 1.
+const GP_AXIS_RIGHT_TRIGGER = 5;
@@ -8,8 +8,38 @@ const KEYBOARD_KEY_COUNT = 193;
 const MOUSE_BUTTON_COUNT = 5;
 /// The maximum number of recognized special mouse buttons.
 const MAX_MOUSE_SPECIAL_BUTTON_COUNT = 32;
+/// The maximum number of characters typed during a frame.
+const MAX_TEXT_INPUT_LENGTH = 32;
 /// The maximum number of recognized fingers.
 const MAX_FINGER_COUNT = 10;
+/// The maximum number of recognized gamepads.
+const MAX_GAMEPAD_COUNT = 4;
+/// The number of recognized gamepad buttons.
+const GAMEPAD_BUTTON_COUNT = 17;
+/// The number of recognized gamepad axes.
+const GAMEPAD_AXIS_COUNT = 6;
+/// The maximum number of characters of the window title.
+const WINDOW_TITLE_MAX_LENGTH = 64;
+/// The maximum number of events sent to the host during a frame.
+const MAX_EVENT_COUNT = 16;
+/// The number of parameters of an event.
+const EVENT_PARAM_COUNT = 4;
+
+/// Window mode where the window is not fullscreen.
+const WINDOW_MODE_WINDOWED = 0u;
+/// Window mode where the window is borderless and fullscreen.
+const WINDOW_MODE_FULLSCREEN = 1u;
+
+/// Event requesting the application to exit.
+const EVENT_EXIT = 0u;
+/// Event requesting to toggle fullscreen mode.
+const EVENT_TOGGLE_FULLSCREEN = 1u;
+/// Event requesting to change cursor visibility.
+///
+/// The first parameter is `1` to show the cursor, or `0` to hide it.
+const EVENT_SET_CURSOR_VISIBILITY = 2u;
+/// The first event kind available for custom events handled by the host.
+const EVENT_CUSTOM = 1024u;
 
 /// Main storage type of the standard library.
 struct Std {
@@ -18,12 +48,20 @@ struct Std {
     _phantom: array<f32, 61>,
     /// Surface properties.
     surface: Surface,
+    /// Window lifecycle state retrieved from the CPU.
+    lifecycle: Lifecycle,
     /// Keyboard state retrieved from the CPU.
     keyboard: Keyboard,
     /// Mouse state retrieved from the CPU.
     mouse: Mouse,
     /// Touch state retrieved from the CPU.
     touch: Touch,
+    /// Gamepad state retrieved from the CPU.
+    gamepads: Gamepads,
+    /// Events sent to the CPU.
+    events: Events,
+    /// Window properties applied by the CPU.
+    window: Window,
 }
 
 /// Time information.
@@ -42,12 +80,42 @@ struct Surface {
     size: vec2u,
 }
 
+/// Window lifecycle state.
+///
+/// When the program is not run in a window, the window is considered as focused and visible.
+struct Lifecycle {
+    /// Whether the window has focus (`1`) or not (`0`).
+    is_focused: u32,
+    /// Whether the window is fully hidden (`1`), e.g. when minimized, or not (`0`).
+    is_occluded: u32,
+    /// The ratio between physical pixels and logical pixels of the window.
+    scale_factor: f32,
+    /// Whether the window has been resized since the previous frame (`1`) or not (`0`).
+    is_just_resized: u32,
+    /// Whether the application has been resumed since the previous frame (`1`) or not (`0`).
+    ///
+    /// This mainly happens on Android when the application comes back to foreground.
+    is_just_resumed: u32,
+}
+
 /// Keyboard state.
 struct Keyboard {
-    /// The state of keyboard keys.
+    /// The state of keyboard keys, based on their physical location.
     ///
-    /// Index is one of `KB_*`.
+    /// Index is one of `KB_*`, which corresponds to the key location on a US keyboard.
     keys: array<InputState, KEYBOARD_KEY_COUNT>,
+    /// The state of keyboard keys, based on the keyboard layout.
+    ///
+    /// Index is one of `KB_*`, which corresponds to the character printed on the key.
+    /// For example, `logical_keys[KB_KEY_A]` is the state of the key typing `a`,
+    /// even if the keyboard is not a US keyboard.
+    logical_keys: array<InputState, KEYBOARD_KEY_COUNT>,
+    /// The number of characters typed during the frame.
+    text_length: u32,
+    /// The characters typed during the frame as Unicode code points, including IME input.
+    ///
+    /// Only the first `text_length` characters are set.
+    text: array<u32, MAX_TEXT_INPUT_LENGTH>,
 }
 
 /// Mouse state.
@@ -89,9 +157,77 @@ struct Finger {
     delta: vec2f,
 }
 
+/// Gamepads state.
+struct Gamepads {
+    /// The state of gamepads, in connection order.
+    pads: array<Gamepad, MAX_GAMEPAD_COUNT>,
+}
+
+/// Gamepad state.
+struct Gamepad {
+    /// Whether the gamepad is connected (`1`) or not (`0`).
+    is_connected: u32,
+    /// The state of gamepad buttons.
+    ///
+    /// Index is one of `GP_BUTTON_*`.
+    buttons: array<InputState, GAMEPAD_BUTTON_COUNT>,
+    /// The value of gamepad axes between -1 and 1.
+    ///
+    /// Index is one of `GP_AXIS_*`.
+    axes: array<f32, GAMEPAD_AXIS_COUNT>,
+}
+
+/// Window properties.
+///
+/// Properties are applied by the host at the end of each frame when they change.
+/// Zero values keep the default properties of the window.
+struct Window {
+    /// Window title as Unicode code points, ending at the first `0`.
+    title: array<u32, WINDOW_TITLE_MAX_LENGTH>,
+    /// Requested size of the window in pixels, or `vec2u(0, 0)` to keep the current size.
+    size: vec2u,
+    /// Either `WINDOW_MODE_WINDOWED` or `WINDOW_MODE_FULLSCREEN`.
+    mode: u32,
+    /// Whether the cursor is hidden (`1`) or visible (`0`).
+    is_cursor_hidden: u32,
+    /// Whether the cursor is grabbed by the window (`1`) or free (`0`).
+    is_cursor_grabbed: u32,
+    /// Whether vertical synchronization is disabled (`1`) or enabled (`0`).
+    is_vsync_disabled: u32,
+}
+
+/// Events sent to the host.
+struct Events {
+    /// The number of events sent during the frame.
+    ///
+    /// It can be greater than `MAX_EVENT_COUNT` if some events have been dropped.
+    count: atomic<u32>,
+    /// The sent events.
+    items: array<Event, MAX_EVENT_COUNT>,
+}
+
+/// Event sent to the host.
+struct Event {
+    /// Either one of `EVENT_*` constants or a custom kind greater than or equal to `EVENT_CUSTOM`.
+    kind: u32,
+    /// The parameters of the event.
+    params: array<u32, EVENT_PARAM_COUNT>,
+}
+
 /// Buffer for performing I/O operations.
 #mod compute
 #import ~.main
 
 /// Main storage variable of the standard library.
 var<storage, read_write> std_: Std;
+
+/// Sends an event to the host.
+///
+/// Events are handled by the host at the end of the frame.
+/// If more than `MAX_EVENT_COUNT` events are sent during a frame, the last ones are dropped.
+fn send_event(kind: u32, params: array<u32, EVENT_PARAM_COUNT>) {
+    let index = atomicAdd(&std_.events.count, 1u);
+    if index < MAX_EVENT_COUNT {
+        std_.events.items[index] = Event(kind, params);
+    }
+}
@@ -10,7 +10,7 @@ const F32_MIN = -3.40282300E+38;
 /// Maximum `f32` value.
 const F32_MAX = 3.40282300E+38;
 
-//! Vector utils.
+/// Vector utils.
 #mod vector
 #import ~.constant
 